@@ -0,0 +1,87 @@
+//! Compares editing performance across buffers as the value grows. Edits in a [RopeBuffer] should
+//! stay roughly constant (logarithmic) while a [LineBuffer] grows linearly with the number of lines
+//! following the edit.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use tty_text::{Key, LineBuffer, RopeBuffer, Text, TextBuffer};
+
+/// The number of lines in each benchmarked value.
+const LINE_COUNTS: [usize; 3] = [1_000, 10_000, 100_000];
+
+/// Create an editor over a buffer with the specified number of log-like lines and the cursor in
+/// the middle.
+fn create_text<B: TextBuffer>(line_count: usize, create: impl Fn(&str) -> B) -> Text<B> {
+    let value = (0..line_count)
+        .map(|index| format!("2024-01-01T00:00:00Z INFO request {index} completed in 12ms"))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Text::with_buffer(create(&value), (20, line_count / 2), true)
+}
+
+/// Benchmark the specified edit, which must leave the value's size unchanged, over each buffer
+/// and value size.
+fn bench_edit(c: &mut Criterion, name: &str, edit: fn(&mut dyn Editor, usize)) {
+    let mut group = c.benchmark_group(name);
+
+    for line_count in LINE_COUNTS {
+        let mut text = create_text(line_count, |value| LineBuffer::from(value));
+        group.bench_function(BenchmarkId::new("LineBuffer", line_count), |b| {
+            b.iter(|| edit(&mut text, line_count))
+        });
+
+        let mut text = create_text(line_count, |value| RopeBuffer::from(value));
+        group.bench_function(BenchmarkId::new("RopeBuffer", line_count), |b| {
+            b.iter(|| edit(&mut text, line_count))
+        });
+    }
+
+    group.finish();
+}
+
+/// The editor operations used by the benchmarks, so they can run over any buffer.
+trait Editor {
+    fn handle_input(&mut self, input: Key);
+    fn delete_range(&mut self, start: (usize, usize), end: (usize, usize));
+    fn undo(&mut self) -> bool;
+}
+
+impl<B: TextBuffer> Editor for Text<B> {
+    fn handle_input(&mut self, input: Key) {
+        Text::handle_input(self, input)
+    }
+
+    fn delete_range(&mut self, start: (usize, usize), end: (usize, usize)) {
+        Text::delete_range(self, start, end)
+    }
+
+    fn undo(&mut self) -> bool {
+        Text::undo(self)
+    }
+}
+
+fn insert_newline(c: &mut Criterion) {
+    // Split and rejoin a line so the value's size stays constant
+    bench_edit(c, "insert_newline", |text, _| {
+        text.handle_input(Key::Enter);
+        text.handle_input(Key::Backspace);
+    });
+}
+
+fn insert_character(c: &mut Criterion) {
+    bench_edit(c, "insert_character", |text, _| {
+        text.handle_input(Key::Char('x'));
+        text.handle_input(Key::Backspace);
+    });
+}
+
+fn delete_lines(c: &mut Criterion) {
+    // Remove and restore a block of lines so the value's size stays constant
+    bench_edit(c, "delete_lines", |text, line_count| {
+        text.delete_range((0, line_count / 2), (0, line_count / 2 + 10));
+        text.undo();
+    });
+}
+
+criterion_group!(benches, insert_newline, insert_character, delete_lines);
+criterion_main!(benches);
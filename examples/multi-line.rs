@@ -2,13 +2,13 @@ use std::io::{stdout, Stdout, Write};
 
 use crossterm::{
     cursor,
-    event::{self, KeyCode, KeyModifiers},
-    queue,
+    event::{self, DisableBracketedPaste, EnableBracketedPaste, KeyCode, KeyModifiers},
+    execute, queue,
     style::Print,
     terminal::{self, enable_raw_mode},
     Result,
 };
-use tty_text::{Key, Text};
+use tty_text::{Key, Text, Viewport};
 
 /// A simple, multi-line CLI text editor built with crossterm.
 fn main() {
@@ -19,55 +19,105 @@ fn execute() -> Result<()> {
     let mut stdout = stdout();
     let mut text = Text::new(true);
 
+    // Reserve the first row for the prompt
+    let (width, height) = terminal::size()?;
+    let mut viewport = Viewport::new(width as usize, height.saturating_sub(1) as usize);
+    viewport.set_scroll_margin(2);
+    text.set_page_height(viewport.height());
+
     enable_raw_mode()?;
-    render(&mut stdout, &text)?;
+    execute!(stdout, EnableBracketedPaste)?;
+    render(&mut stdout, &text, &viewport)?;
 
     loop {
         let event = event::read()?;
 
+        if let event::Event::Paste(value) = &event {
+            text.handle_input(Key::Paste(value.clone()));
+        }
+
         if let event::Event::Key(key_event) = event {
             if key_event.modifiers == KeyModifiers::CONTROL && key_event.code == KeyCode::Char('c')
             {
                 break;
             }
 
+            let shift = key_event.modifiers.contains(KeyModifiers::SHIFT);
+            let control = key_event.modifiers.contains(KeyModifiers::CONTROL);
+
             match key_event.code {
+                KeyCode::Esc if text.search_query().is_some() => text.handle_input(Key::Escape),
                 KeyCode::Esc => break,
+                KeyCode::Char('f') if control => text.handle_input(Key::Search),
+                KeyCode::Char('z') if control => text.handle_input(Key::Undo),
+                KeyCode::Char('y') if control => text.handle_input(Key::Redo),
+                KeyCode::Char('w') if control => text.handle_input(Key::DeleteWordBackward),
                 KeyCode::Char(ch) => text.handle_input(Key::Char(ch)),
+                KeyCode::Backspace if control => text.handle_input(Key::DeleteWordBackward),
                 KeyCode::Backspace => text.handle_input(Key::Backspace),
+                KeyCode::PageUp => text.handle_input(Key::PageUp),
+                KeyCode::PageDown => text.handle_input(Key::PageDown),
+                KeyCode::Home if control => text.handle_input(Key::DocumentStart),
+                KeyCode::Home => text.handle_input(Key::Home),
+                KeyCode::End if control => text.handle_input(Key::DocumentEnd),
+                KeyCode::End => text.handle_input(Key::End),
+                KeyCode::Delete if control => text.handle_input(Key::DeleteWordForward),
+                KeyCode::Delete => text.handle_input(Key::Delete),
                 KeyCode::Enter => text.handle_input(Key::Enter),
+                KeyCode::Up if shift => text.handle_input(Key::SelectUp),
                 KeyCode::Up => text.handle_input(Key::Up),
+                KeyCode::Down if shift => text.handle_input(Key::SelectDown),
                 KeyCode::Down => text.handle_input(Key::Down),
+                KeyCode::Left if control => text.handle_input(Key::WordLeft),
+                KeyCode::Left if shift => text.handle_input(Key::SelectLeft),
                 KeyCode::Left => text.handle_input(Key::Left),
+                KeyCode::Right if control => text.handle_input(Key::WordRight),
+                KeyCode::Right if shift => text.handle_input(Key::SelectRight),
                 KeyCode::Right => text.handle_input(Key::Right),
                 _ => {}
             }
         }
 
-        render(&mut stdout, &text)?;
+        if let event::Event::Resize(width, height) = event {
+            viewport.set_size(width as usize, height.saturating_sub(1) as usize);
+            text.set_page_height(viewport.height());
+        }
+
+        viewport.scroll_to_cursor(&text);
+        render(&mut stdout, &text, &viewport)?;
     }
 
+    execute!(stdout, DisableBracketedPaste)?;
+
     Ok(())
 }
 
-fn render(stdout: &mut Stdout, text: &Text) -> Result<()> {
+fn render(stdout: &mut Stdout, text: &Text, viewport: &Viewport) -> Result<()> {
     queue!(stdout, terminal::Clear(terminal::ClearType::All))?;
 
     queue!(stdout, cursor::MoveTo(0, 0))?;
-    queue!(
-        stdout,
-        Print("Enter text (arrows to move cursor, Ctrl/Cmd+C or Esc to quit):")
-    )?;
-
-    let lines = text.lines().iter().enumerate();
-    for (line_index, line) in lines {
-        queue!(stdout, cursor::MoveTo(0, line_index as u16 + 1))?;
-        queue!(stdout, Print(line))?;
+    match text.search_query() {
+        Some(query) => queue!(
+            stdout,
+            Print(format!("Search (Enter to accept, Esc to cancel): {query}"))
+        )?,
+        None => queue!(
+            stdout,
+            Print(
+                "Enter text (arrows to move cursor, Ctrl+F to search, Ctrl/Cmd+C or Esc to quit):"
+            )
+        )?,
     }
 
-    let position = text.cursor();
-    let (x, y) = (position.0 as u16, position.1 as u16);
-    queue!(stdout, cursor::MoveTo(x, y + 1))?;
+    let visible_lines = viewport.visible_lines(text);
+    for (row, line_index) in visible_lines.enumerate() {
+        queue!(stdout, cursor::MoveTo(0, row as u16 + 1))?;
+        queue!(stdout, Print(viewport.visible_slice(text, line_index)))?;
+    }
+
+    if let Some((x, y)) = viewport.cursor_position(text) {
+        queue!(stdout, cursor::MoveTo(x as u16, y as u16 + 1))?;
+    }
 
     stdout.flush()?;
 
@@ -2,13 +2,13 @@ use std::io::{stdout, Stdout, Write};
 
 use crossterm::{
     cursor,
-    event::{self, KeyCode, KeyModifiers},
-    queue,
+    event::{self, DisableBracketedPaste, EnableBracketedPaste, KeyCode, KeyModifiers},
+    execute, queue,
     style::Print,
     terminal::{self, enable_raw_mode},
     Result,
 };
-use tty_text::{Key, Text};
+use tty_text::{Key, Text, Viewport};
 
 /// A simple, single-line CLI text editor built with crossterm.
 fn main() {
@@ -19,35 +19,63 @@ fn execute() -> Result<()> {
     let mut stdout = stdout();
     let mut text = Text::new(false);
 
+    // A fixed-width field which scrolls horizontally to keep the cursor visible
+    let mut viewport = Viewport::new(20, 1);
+
     enable_raw_mode()?;
-    render(&mut stdout, &text)?;
+    execute!(stdout, EnableBracketedPaste)?;
+    render(&mut stdout, &text, &viewport)?;
 
     loop {
         let event = event::read()?;
 
+        if let event::Event::Paste(value) = &event {
+            text.handle_input(Key::Paste(value.clone()));
+        }
+
         if let event::Event::Key(key_event) = event {
             if key_event.modifiers == KeyModifiers::CONTROL && key_event.code == KeyCode::Char('c')
             {
                 break;
             }
 
+            let shift = key_event.modifiers.contains(KeyModifiers::SHIFT);
+            let control = key_event.modifiers.contains(KeyModifiers::CONTROL);
+
             match key_event.code {
                 KeyCode::Esc => break,
+                KeyCode::Char('z') if control => text.handle_input(Key::Undo),
+                KeyCode::Char('y') if control => text.handle_input(Key::Redo),
+                KeyCode::Char('w') if control => text.handle_input(Key::DeleteWordBackward),
                 KeyCode::Char(ch) => text.handle_input(Key::Char(ch)),
+                KeyCode::Backspace if control => text.handle_input(Key::DeleteWordBackward),
                 KeyCode::Backspace => text.handle_input(Key::Backspace),
+                KeyCode::Home if control => text.handle_input(Key::DocumentStart),
+                KeyCode::Home => text.handle_input(Key::Home),
+                KeyCode::End if control => text.handle_input(Key::DocumentEnd),
+                KeyCode::End => text.handle_input(Key::End),
+                KeyCode::Delete if control => text.handle_input(Key::DeleteWordForward),
+                KeyCode::Delete => text.handle_input(Key::Delete),
+                KeyCode::Left if control => text.handle_input(Key::WordLeft),
+                KeyCode::Left if shift => text.handle_input(Key::SelectLeft),
                 KeyCode::Left => text.handle_input(Key::Left),
+                KeyCode::Right if control => text.handle_input(Key::WordRight),
+                KeyCode::Right if shift => text.handle_input(Key::SelectRight),
                 KeyCode::Right => text.handle_input(Key::Right),
                 _ => {}
             }
         }
 
-        render(&mut stdout, &text)?;
+        viewport.scroll_to_cursor(&text);
+        render(&mut stdout, &text, &viewport)?;
     }
 
+    execute!(stdout, DisableBracketedPaste)?;
+
     Ok(())
 }
 
-fn render(stdout: &mut Stdout, text: &Text) -> Result<()> {
+fn render(stdout: &mut Stdout, text: &Text, viewport: &Viewport) -> Result<()> {
     queue!(stdout, terminal::Clear(terminal::ClearType::All))?;
 
     queue!(stdout, cursor::MoveTo(0, 0))?;
@@ -56,12 +84,15 @@ fn render(stdout: &mut Stdout, text: &Text) -> Result<()> {
         Print("Enter text (arrows to move cursor, Ctrl/Cmd+C or Esc to quit):")
     )?;
 
+    let (slice, cursor_offset) = viewport.cursor_slice(text);
+
     queue!(stdout, cursor::MoveTo(0, 1))?;
-    queue!(stdout, Print(text.value()))?;
+    queue!(
+        stdout,
+        Print(format!("[{:width$}]", slice, width = viewport.width()))
+    )?;
 
-    let position = text.cursor();
-    let (x, y) = (position.0 as u16, position.1 as u16);
-    queue!(stdout, cursor::MoveTo(x, y + 1))?;
+    queue!(stdout, cursor::MoveTo(cursor_offset as u16 + 1, 1))?;
 
     stdout.flush()?;
 
@@ -0,0 +1,313 @@
+//! Storage for an editor's lines.
+
+use std::borrow::Cow;
+
+use ropey::Rope;
+
+/// Storage for an editor's value as lines separated by `\n`. Implement this to run a [Text] over
+/// a custom structure, such as a buffer mirrored to a database, and check the implementation with
+/// [crate::conformance::run].
+///
+/// Positions are raw (bytes, lines) pairs. The editor only passes positions within the buffer's
+/// current value which fall on character boundaries, and only inserts text with `\n` line breaks.
+/// Buffers must likewise separate lines with `\n` alone, so a line never contains `\r`.
+///
+/// [Text]: crate::Text
+pub trait TextBuffer {
+    /// The number of lines in this buffer, which must always be at least one.
+    fn line_count(&self) -> usize;
+
+    /// The specified line's content, excluding its line break.
+    fn line(&self, line_index: usize) -> Cow<'_, str>;
+
+    /// The specified line's length in bytes, excluding its line break.
+    fn line_len(&self, line_index: usize) -> usize {
+        self.line(line_index).len()
+    }
+
+    /// This buffer's lines joined by `\n`.
+    fn value(&self) -> String {
+        (0..self.line_count())
+            .map(|line_index| self.line(line_index))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Insert the specified text, with lines separated by `\n`, at the specified raw
+    /// (bytes, lines) position.
+    fn insert(&mut self, position: (usize, usize), text: &str);
+
+    /// Remove the text between the specified raw (bytes, lines) positions, returning it with lines
+    /// joined by `\n`.
+    fn remove(&mut self, start: (usize, usize), end: (usize, usize)) -> String;
+}
+
+/// A buffer storing each line as a separate string. Edits within a line are fast, but inserting or
+/// removing lines shifts every following line. This is [Text]'s default buffer.
+///
+/// [Text]: crate::Text
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineBuffer {
+    /// The buffer's lines, of which there is at least one.
+    lines: Vec<String>,
+}
+
+impl LineBuffer {
+    /// Create a new buffer with a single, empty line.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Default for LineBuffer {
+    fn default() -> Self {
+        Self {
+            lines: vec![String::new()],
+        }
+    }
+}
+
+impl From<&str> for LineBuffer {
+    /// Create a buffer from the specified value, with lines separated by `\n`, `\r\n` or `\r`.
+    fn from(value: &str) -> Self {
+        let value = crate::normalize_newlines(value, true);
+
+        Self {
+            lines: value.split('\n').map(|line| line.to_string()).collect(),
+        }
+    }
+}
+
+impl From<Vec<String>> for LineBuffer {
+    /// Create a buffer from the specified lines. An empty vector creates a single, empty line.
+    fn from(lines: Vec<String>) -> Self {
+        if lines.is_empty() {
+            return Self::new();
+        }
+
+        Self { lines }
+    }
+}
+
+impl TextBuffer for LineBuffer {
+    fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    fn line(&self, line_index: usize) -> Cow<'_, str> {
+        Cow::Borrowed(&self.lines[line_index])
+    }
+
+    fn line_len(&self, line_index: usize) -> usize {
+        self.lines[line_index].len()
+    }
+
+    fn value(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    fn insert(&mut self, position: (usize, usize), text: &str) {
+        let mut segments = text.split('\n');
+        let first_segment = segments.next().unwrap_or_default();
+        let mut new_lines: Vec<String> = segments.map(|segment| segment.to_string()).collect();
+
+        let Some(last_line) = new_lines.last_mut() else {
+            self.lines[position.1].insert_str(position.0, first_segment);
+            return;
+        };
+
+        // Move the content after the position onto the end of the last inserted line
+        let suffix = self.lines[position.1].split_off(position.0);
+        last_line.push_str(&suffix);
+
+        self.lines[position.1].push_str(first_segment);
+        self.lines.splice(position.1 + 1..position.1 + 1, new_lines);
+    }
+
+    fn remove(&mut self, start: (usize, usize), end: (usize, usize)) -> String {
+        if start.1 == end.1 {
+            return self.lines[start.1].drain(start.0..end.0).collect();
+        }
+
+        let suffix = self.lines[end.1].split_off(end.0);
+        let removed_lines: Vec<String> = self.lines.drain(start.1 + 1..=end.1).collect();
+
+        let mut removed = self.lines[start.1].split_off(start.0);
+        for line in removed_lines {
+            removed.push('\n');
+            removed.push_str(&line);
+        }
+
+        self.lines[start.1].push_str(&suffix);
+
+        removed
+    }
+}
+
+/// A buffer storing its value in a rope, whose edits take logarithmic time in the value's length
+/// regardless of where they are made. Suited to large values such as multi-megabyte logs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RopeBuffer {
+    /// The buffer's value. Ropey's `unicode_lines` and `cr_lines` features are disabled so only
+    /// `\n` separates lines.
+    rope: Rope,
+}
+
+impl RopeBuffer {
+    /// Create a new buffer with a single, empty line.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the rope's character index of the specified raw (bytes, lines) position.
+    fn get_char_index(&self, position: (usize, usize)) -> usize {
+        self.rope
+            .byte_to_char(self.rope.line_to_byte(position.1) + position.0)
+    }
+}
+
+impl From<&str> for RopeBuffer {
+    /// Create a buffer from the specified value, with lines separated by `\n`, `\r\n` or `\r`.
+    fn from(value: &str) -> Self {
+        Self {
+            rope: Rope::from_str(&crate::normalize_newlines(value, true)),
+        }
+    }
+}
+
+impl TextBuffer for RopeBuffer {
+    fn line_count(&self) -> usize {
+        self.rope.len_lines()
+    }
+
+    fn line(&self, line_index: usize) -> Cow<'_, str> {
+        let line = self.rope.line(line_index);
+        let line = match line.len_chars().checked_sub(1) {
+            Some(last) if line.char(last) == '\n' => line.slice(..last),
+            _ => line,
+        };
+
+        // Lines spanning multiple chunks must be copied
+        match line.as_str() {
+            Some(line) => Cow::Borrowed(line),
+            None => Cow::Owned(line.to_string()),
+        }
+    }
+
+    fn line_len(&self, line_index: usize) -> usize {
+        let start = self.rope.line_to_byte(line_index);
+        let end = if line_index + 1 < self.rope.len_lines() {
+            self.rope.line_to_byte(line_index + 1) - 1
+        } else {
+            self.rope.len_bytes()
+        };
+
+        end - start
+    }
+
+    fn value(&self) -> String {
+        self.rope.to_string()
+    }
+
+    fn insert(&mut self, position: (usize, usize), text: &str) {
+        let char_index = self.get_char_index(position);
+        self.rope.insert(char_index, text);
+    }
+
+    fn remove(&mut self, start: (usize, usize), end: (usize, usize)) -> String {
+        let range = self.get_char_index(start)..self.get_char_index(end);
+        let removed = self.rope.slice(range.clone()).to_string();
+        self.rope.remove(range);
+
+        removed
+    }
+}
+
+/// A borrowing iterator over an editor's lines, excluding their line breaks.
+#[derive(Debug)]
+pub struct Lines<'a, B> {
+    /// The buffer being iterated.
+    buffer: &'a B,
+
+    /// The indices of the lines not yet yielded.
+    range: std::ops::Range<usize>,
+}
+
+impl<'a, B: TextBuffer> Lines<'a, B> {
+    /// Create a new iterator over all of the specified buffer's lines.
+    pub(crate) fn new(buffer: &'a B) -> Self {
+        Self {
+            buffer,
+            range: 0..buffer.line_count(),
+        }
+    }
+}
+
+impl<'a, B: TextBuffer> Iterator for Lines<'a, B> {
+    type Item = Cow<'a, str>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.range.next().map(|index| self.buffer.line(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.range.nth(n).map(|index| self.buffer.line(index))
+    }
+}
+
+impl<B: TextBuffer> DoubleEndedIterator for Lines<'_, B> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.range.next_back().map(|index| self.buffer.line(index))
+    }
+}
+
+impl<B: TextBuffer> ExactSizeIterator for Lines<'_, B> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_buffer_from_empty_lines() {
+        let buffer = LineBuffer::from(Vec::new());
+
+        assert_eq!(1, buffer.line_count());
+        assert_eq!("", buffer.line(0));
+    }
+
+    #[test]
+    fn lines_iterator() {
+        let buffer = RopeBuffer::from("a\nb\nc");
+        let mut lines = Lines::new(&buffer);
+
+        assert_eq!(3, lines.len());
+        assert_eq!(Some(Cow::Borrowed("c")), lines.next_back());
+        assert_eq!(Some(Cow::Borrowed("a")), lines.next());
+        assert_eq!(1, lines.len());
+    }
+
+    #[test]
+    fn rope_line_across_chunks() {
+        let line = "abcdefghij".repeat(1000);
+        let buffer = RopeBuffer::from(format!("{line}\n{line}").as_str());
+
+        assert_eq!(line, buffer.line(1));
+        assert_eq!(line.len(), buffer.line_len(1));
+    }
+
+    #[test]
+    fn from_normalizes_line_breaks() {
+        let value = "a\r\nb\rc\n";
+        let expected = ["a", "b", "c", ""];
+
+        let buffer = LineBuffer::from(value);
+        assert_eq!(expected.to_vec(), Lines::new(&buffer).collect::<Vec<_>>());
+
+        let buffer = RopeBuffer::from(value);
+        assert_eq!(expected.to_vec(), Lines::new(&buffer).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,361 @@
+//! Cut, copy and paste through a pluggable clipboard.
+
+use crate::{history::EditKind, Text, TextBuffer};
+
+/// Storage for text cut or copied from an editor, which may be backed by a system clipboard or a
+/// terminal escape sequence such as OSC 52. Clipboards must be [Send] and [Sync] so editors can
+/// move and be shared across threads.
+pub trait Clipboard: Send + Sync {
+    /// The clipboard's current contents, if any.
+    fn get(&mut self) -> Option<String>;
+
+    /// Replace the clipboard's contents.
+    fn set(&mut self, contents: String);
+}
+
+/// A clipboard which stores its contents in memory. This is the editor's default clipboard.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MemoryClipboard {
+    /// The clipboard's contents, if any have been set.
+    contents: Option<String>,
+}
+
+impl MemoryClipboard {
+    /// Create a new, empty clipboard.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Clipboard for MemoryClipboard {
+    fn get(&mut self) -> Option<String> {
+        self.contents.clone()
+    }
+
+    fn set(&mut self, contents: String) {
+        self.contents = Some(contents);
+    }
+}
+
+impl<B: TextBuffer> Text<B> {
+    /// Replace the clipboard used by this editor's cut, copy and paste operations.
+    pub fn set_clipboard(&mut self, clipboard: impl Clipboard + 'static) {
+        self.clipboard = Box::new(clipboard);
+    }
+
+    /// The clipboard used by this editor's cut, copy and paste operations.
+    pub fn clipboard(&mut self) -> &mut dyn Clipboard {
+        self.clipboard.as_mut()
+    }
+
+    /// Copy the selected text to the clipboard or, if nothing is selected, the cursor's line.
+    /// In multi-line mode, a copied line includes its trailing newline.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_text::Text;
+    ///
+    /// let mut text = Text::from("abc\ndef", (1, 0), true);
+    ///
+    /// text.copy();
+    /// text.set_cursor((0, 1));
+    /// text.paste();
+    ///
+    /// assert_eq!("abc\nabc\ndef", text.value());
+    /// ```
+    pub fn copy(&mut self) {
+        let contents = match self.selected_text() {
+            Some(selected_text) => selected_text,
+            None => self.get_line_contents(),
+        };
+
+        self.clipboard.set(contents);
+    }
+
+    /// Move the selected text to the clipboard or, if nothing is selected, the cursor's line.
+    /// In multi-line mode, a cut line includes its trailing newline and is removed entirely, unless
+    /// it's the only line, which is cleared instead.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_text::Text;
+    ///
+    /// let mut text = Text::from("Hello, world!", (0, 0), false);
+    ///
+    /// text.set_selection((5, 0), (12, 0));
+    /// text.cut();
+    /// text.set_cursor((0, 0));
+    /// text.paste();
+    ///
+    /// assert_eq!(", worldHello!", text.value());
+    /// ```
+    pub fn cut(&mut self) {
+        // Cutting the only line leaves an empty line behind, so no line break is cut with it
+        let is_only_line = self.selection().is_none() && self.buffer.line_count() == 1;
+        if is_only_line {
+            self.clipboard.set(self.buffer.line(0).into_owned());
+        } else {
+            self.copy();
+        }
+
+        self.transact(EditKind::Other, |text| {
+            if text.delete_selection() {
+                return;
+            }
+
+            let line_index = text.cursor.1;
+            let line_end = (text.buffer.line_len(line_index), line_index);
+
+            if !text.multi_line || text.buffer.line_count() == 1 {
+                text.splice((0, line_index), line_end, "");
+                text.cursor = (0, line_index);
+            } else if line_index + 1 < text.buffer.line_count() {
+                text.splice((0, line_index), (0, line_index + 1), "");
+                text.cursor = text.clamp_position(text.cursor);
+            } else {
+                let previous_line_end = (text.buffer.line_len(line_index - 1), line_index - 1);
+                text.splice(previous_line_end, line_end, "");
+                text.cursor = text.clamp_position((text.cursor.0, line_index - 1));
+            }
+
+            text.update_preferred_column();
+        });
+    }
+
+    /// Insert the clipboard's contents at the cursor, replacing any selection, as by
+    /// [Text::insert_str].
+    pub fn paste(&mut self) {
+        if let Some(contents) = self.clipboard.get() {
+            self.insert_str(&contents);
+        }
+    }
+
+    /// Get the cursor's line as clipboard contents.
+    fn get_line_contents(&self) -> String {
+        let line = self.buffer.line(self.cursor.1).into_owned();
+
+        if self.multi_line {
+            line + "\n"
+        } else {
+            line
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::{Key, Range};
+
+    /// A clipboard whose contents are shared with the test.
+    struct SharedClipboard(Arc<Mutex<Option<String>>>);
+
+    impl Clipboard for SharedClipboard {
+        fn get(&mut self) -> Option<String> {
+            self.0.lock().unwrap().clone()
+        }
+
+        fn set(&mut self, contents: String) {
+            *self.0.lock().unwrap() = Some(contents);
+        }
+    }
+
+    #[test]
+    fn memory_clipboard() {
+        let mut clipboard = MemoryClipboard::new();
+        assert_eq!(None, clipboard.get());
+
+        clipboard.set("abc".to_string());
+        assert_eq!(Some("abc".to_string()), clipboard.get());
+    }
+
+    #[test]
+    fn copy_selection() {
+        let mut text = Text::from("abc\ndef", (0, 0), true);
+
+        text.set_selection((1, 0), (2, 1));
+        text.copy();
+
+        assert_eq!(Some("bc\nde".to_string()), text.clipboard().get());
+        assert_eq!("abc\ndef", text.value());
+        assert_eq!(Some(Range::new((1, 0), (2, 1))), text.selection());
+    }
+
+    #[test]
+    fn copy_line() {
+        let mut text = Text::from("abc\ndef", (1, 1), true);
+
+        text.copy();
+
+        assert_eq!(Some("def\n".to_string()), text.clipboard().get());
+    }
+
+    #[test]
+    fn copy_line_single_line() {
+        let mut text = Text::from("abc", (1, 0), false);
+
+        text.copy();
+
+        assert_eq!(Some("abc".to_string()), text.clipboard().get());
+    }
+
+    #[test]
+    fn cut_selection() {
+        let mut text = Text::from("abc\ndef", (0, 0), true);
+
+        text.set_selection((1, 0), (2, 1));
+        text.cut();
+
+        assert_eq!(Some("bc\nde".to_string()), text.clipboard().get());
+        assert_eq!("af", text.value());
+        assert_eq!((1, 0), text.cursor());
+    }
+
+    #[test]
+    fn cut_line() {
+        let mut text = Text::from("abc\ndef\nghi", (2, 1), true);
+
+        text.cut();
+
+        assert_eq!(Some("def\n".to_string()), text.clipboard().get());
+        assert_eq!("abc\nghi", text.value());
+        assert_eq!((2, 1), text.cursor());
+    }
+
+    #[test]
+    fn cut_last_line() {
+        let mut text = Text::from("abc\nd", (1, 1), true);
+
+        text.cut();
+
+        assert_eq!(Some("d\n".to_string()), text.clipboard().get());
+        assert_eq!("abc", text.value());
+        assert_eq!((1, 0), text.cursor());
+    }
+
+    #[test]
+    fn cut_only_line() {
+        let mut text = Text::from("abc", (2, 0), false);
+
+        text.cut();
+
+        assert_eq!(Some("abc".to_string()), text.clipboard().get());
+        assert_eq!("", text.value());
+        assert_eq!((0, 0), text.cursor());
+    }
+
+    #[test]
+    fn cut_only_line_multi_line() {
+        let mut text = Text::from("abc", (2, 0), true);
+
+        text.cut();
+        assert_eq!(Some("abc".to_string()), text.clipboard().get());
+        assert_eq!("", text.value());
+
+        text.paste();
+        assert_eq!("abc", text.value());
+        assert_eq!((3, 0), text.cursor());
+    }
+
+    #[test]
+    fn cut_undo() {
+        let mut text = Text::from("abc\ndef\nghi", (2, 1), true);
+
+        text.cut();
+        text.handle_input(Key::Undo);
+
+        assert_eq!("abc\ndef\nghi", text.value());
+        assert_eq!((2, 1), text.cursor());
+    }
+
+    #[test]
+    fn paste_multi_line() {
+        let mut text = Text::from("ab", (1, 0), true);
+        text.clipboard().set("1\r\n2\n3".to_string());
+
+        text.paste();
+
+        assert_eq!("a1\n2\n3b", text.value());
+        assert_eq!(vec!["a1", "2", "3b"], text.lines().collect::<Vec<_>>());
+        assert_eq!((1, 2), text.cursor());
+    }
+
+    #[test]
+    fn paste_single_line() {
+        let mut text = Text::from("ab", (1, 0), false);
+        text.clipboard().set("1\r\n2\n3".to_string());
+
+        text.paste();
+
+        assert_eq!("a123b", text.value());
+        assert_eq!((4, 0), text.cursor());
+    }
+
+    #[test]
+    fn paste_replaces_selection() {
+        let mut text = Text::from("abcd", (0, 0), true);
+        text.clipboard().set("X".to_string());
+
+        text.set_selection((1, 0), (3, 0));
+        text.paste();
+
+        assert_eq!("aXd", text.value());
+        assert_eq!((2, 0), text.cursor());
+    }
+
+    #[test]
+    fn paste_empty_clipboard() {
+        let mut text = Text::from("abc", (1, 0), true);
+
+        text.paste();
+
+        assert_eq!("abc", text.value());
+        assert!(!text.undo());
+    }
+
+    #[test]
+    fn paste_undo() {
+        let mut text = Text::from("ab", (1, 0), true);
+        text.clipboard().set("1\n2".to_string());
+
+        text.paste();
+        text.handle_input(Key::Undo);
+
+        assert_eq!("ab", text.value());
+        assert_eq!((1, 0), text.cursor());
+    }
+
+    #[test]
+    fn custom_clipboard() {
+        let contents = Arc::new(Mutex::new(None));
+
+        let mut text = Text::from("abc", (0, 0), true);
+        text.set_clipboard(SharedClipboard(contents.clone()));
+
+        text.set_selection((0, 0), (2, 0));
+        text.copy();
+        assert_eq!(Some("ab".to_string()), *contents.lock().unwrap());
+
+        *contents.lock().unwrap() = Some("xyz".to_string());
+        text.paste();
+        assert_eq!("xyzc", text.value());
+    }
+
+    #[test]
+    fn text_is_send() {
+        fn assert_send<T: Send>() {}
+
+        assert_send::<Text>();
+        assert_send::<Text<crate::RopeBuffer>>();
+    }
+
+    #[test]
+    fn text_is_sync() {
+        fn assert_sync<T: Sync>() {}
+
+        assert_sync::<Text>();
+        assert_sync::<Text<crate::RopeBuffer>>();
+    }
+}
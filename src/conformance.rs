@@ -0,0 +1,270 @@
+//! A conformance suite for [TextBuffer] implementations.
+//!
+//! # Examples
+//! ```
+//! use tty_text::{conformance, RopeBuffer};
+//!
+//! // Typically called from a backend's own #[test]
+//! conformance::run(|value| RopeBuffer::from(value));
+//! ```
+
+use crate::{Key, LineBuffer, Text, TextBuffer};
+
+/// Check that buffers from the specified constructor, which creates a buffer from a value with
+/// lines separated by `\n`, behave as [TextBuffer] requires, both directly and beneath a [Text].
+///
+/// # Panics
+/// If the buffer deviates from the expected behavior, describing the first deviation.
+pub fn run<B: TextBuffer>(create: impl Fn(&str) -> B) {
+    check_empty(&create);
+    check_lines(&create);
+    check_insert(&create);
+    check_remove(&create);
+    check_editor(&create);
+}
+
+/// Check that a buffer created from an empty value has a single, empty line.
+fn check_empty<B: TextBuffer>(create: &impl Fn(&str) -> B) {
+    let buffer = create("");
+
+    assert_eq!(1, buffer.line_count(), "an empty buffer has one line");
+    assert_eq!("", buffer.line(0), "an empty buffer's line is empty");
+    assert_eq!(
+        0,
+        buffer.line_len(0),
+        "an empty buffer's line has no length"
+    );
+    assert_eq!("", buffer.value(), "an empty buffer has no value");
+}
+
+/// Check that a buffer's lines match the value it was created from.
+fn check_lines<B: TextBuffer>(create: &impl Fn(&str) -> B) {
+    let value = "ab\n\n日本\u{1F600}\ne\u{301}\n";
+    let buffer = create(value);
+
+    let expected = ["ab", "", "日本\u{1F600}", "e\u{301}", ""];
+    assert_eq!(
+        expected.len(),
+        buffer.line_count(),
+        "line count of {value:?}"
+    );
+
+    for (line_index, line) in expected.iter().enumerate() {
+        assert_eq!(
+            *line,
+            buffer.line(line_index),
+            "line {line_index} of {value:?}"
+        );
+        assert_eq!(
+            line.len(),
+            buffer.line_len(line_index),
+            "length of line {line_index} of {value:?}"
+        );
+    }
+
+    assert_eq!(value, buffer.value(), "value of {value:?}");
+}
+
+/// Check insertions within a line, across lines and at the value's edges.
+fn check_insert<B: TextBuffer>(create: &impl Fn(&str) -> B) {
+    let cases = [
+        ("abc", (1, 0), "X", "aXbc"),
+        ("abc", (3, 0), "日本", "abc日本"),
+        ("abc\ndef", (0, 0), "X\n", "X\nabc\ndef"),
+        ("abc\ndef", (1, 1), "1\n2\n3", "abc\nd1\n2\n3ef"),
+        ("abc\ndef", (3, 1), "\n", "abc\ndef\n"),
+        ("日本", (3, 0), "\n", "日\n本"),
+    ];
+
+    for (value, position, text, expected) in cases {
+        let mut buffer = create(value);
+        buffer.insert(position, text);
+
+        assert_buffer(
+            &buffer,
+            expected,
+            &format!("inserting {text:?} into {value:?} at {position:?}"),
+        );
+    }
+}
+
+/// Check removals within a line, across lines and of the entire value.
+fn check_remove<B: TextBuffer>(create: &impl Fn(&str) -> B) {
+    let cases = [
+        ("abc", (1, 0), (2, 0), "b", "ac"),
+        ("abc\ndef\nghi", (1, 0), (2, 1), "bc\nde", "af\nghi"),
+        ("abc\ndef", (3, 0), (0, 1), "\n", "abcdef"),
+        ("abc\ndef\n", (0, 0), (0, 2), "abc\ndef\n", ""),
+        ("日本\n語", (3, 0), (3, 1), "本\n語", "日"),
+    ];
+
+    for (value, start, end, removed, expected) in cases {
+        let mut buffer = create(value);
+        let context = format!("removing {start:?} to {end:?} from {value:?}");
+
+        assert_eq!(removed, buffer.remove(start, end), "{context}");
+        assert_buffer(&buffer, expected, &context);
+    }
+}
+
+/// A sequence of inputs applied to an editor through the specified input function.
+type EditorStep = fn(&mut dyn FnMut(Key));
+
+/// Check that an editor over the buffer behaves identically to one over a [LineBuffer].
+fn check_editor<B: TextBuffer>(create: &impl Fn(&str) -> B) {
+    let value = "Hello,\n - world!\n";
+    let mut expected = Text::with_buffer(LineBuffer::from(value), (6, 0), true);
+    let mut actual = Text::with_buffer(create(value), (6, 0), true);
+
+    let steps: [EditorStep; 8] = [
+        |input| input(Key::Enter),
+        |input| input(Key::Char('日')),
+        |input| input(Key::Down),
+        |input| {
+            input(Key::End);
+            input(Key::Enter);
+        },
+        |input| {
+            input(Key::Up);
+            input(Key::SelectUp);
+            input(Key::Backspace);
+        },
+        |input| input(Key::Paste("a\nb\r\nc".to_string())),
+        |input| {
+            input(Key::DocumentStart);
+            input(Key::Delete);
+            input(Key::DeleteWordForward);
+        },
+        |input| {
+            input(Key::Undo);
+            input(Key::Undo);
+            input(Key::Redo);
+        },
+    ];
+
+    for (index, step) in steps.iter().enumerate() {
+        step(&mut |key: Key| expected.handle_input(key));
+        step(&mut |key: Key| actual.handle_input(key));
+
+        let context = format!("editor step {index}");
+        assert_eq!(expected.value(), actual.value(), "value after {context}");
+        assert_eq!(expected.cursor(), actual.cursor(), "cursor after {context}");
+        assert_buffer(actual.buffer(), &expected.value(), &context);
+    }
+
+    let single_line = Text::with_buffer(create("a\nb\nc"), (9, 0), false);
+    assert_buffer(
+        single_line.buffer(),
+        "abc",
+        "joining lines in single-line mode",
+    );
+    assert_eq!((3, 0), single_line.cursor(), "cursor in single-line mode");
+}
+
+/// Assert that the specified buffer's lines and value match the specified value.
+fn assert_buffer<B: TextBuffer>(buffer: &B, expected: &str, context: &str) {
+    assert_eq!(expected, buffer.value(), "value after {context}");
+
+    let lines: Vec<&str> = expected.split('\n').collect();
+    assert_eq!(
+        lines.len(),
+        buffer.line_count(),
+        "line count after {context}"
+    );
+
+    for (line_index, line) in lines.iter().enumerate() {
+        assert_eq!(
+            *line,
+            buffer.line(line_index),
+            "line {line_index} after {context}"
+        );
+        assert_eq!(
+            line.len(),
+            buffer.line_len(line_index),
+            "length of line {line_index} after {context}"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::*;
+    use crate::RopeBuffer;
+
+    /// A minimal buffer relying on [TextBuffer]'s provided methods, as a host-owned buffer might.
+    struct StringBuffer(String);
+
+    impl TextBuffer for StringBuffer {
+        fn line_count(&self) -> usize {
+            self.0.split('\n').count()
+        }
+
+        fn line(&self, line_index: usize) -> Cow<'_, str> {
+            Cow::Borrowed(self.0.split('\n').nth(line_index).unwrap())
+        }
+
+        fn insert(&mut self, position: (usize, usize), text: &str) {
+            let offset = self.get_offset(position);
+            self.0.insert_str(offset, text);
+        }
+
+        fn remove(&mut self, start: (usize, usize), end: (usize, usize)) -> String {
+            let range = self.get_offset(start)..self.get_offset(end);
+            self.0.drain(range).collect()
+        }
+    }
+
+    impl StringBuffer {
+        fn get_offset(&self, position: (usize, usize)) -> usize {
+            let preceding: usize = self
+                .0
+                .split('\n')
+                .take(position.1)
+                .map(|line| line.len() + 1)
+                .sum();
+
+            preceding + position.0
+        }
+    }
+
+    #[test]
+    fn line_buffer() {
+        run(|value| LineBuffer::from(value));
+    }
+
+    #[test]
+    fn rope_buffer() {
+        run(|value| RopeBuffer::from(value));
+    }
+
+    #[test]
+    fn custom_buffer() {
+        run(|value| StringBuffer(value.to_string()));
+    }
+
+    #[test]
+    #[should_panic(expected = "value after inserting")]
+    fn nonconforming_buffer() {
+        struct IgnoringBuffer(LineBuffer);
+
+        impl TextBuffer for IgnoringBuffer {
+            fn line_count(&self) -> usize {
+                self.0.line_count()
+            }
+
+            fn line(&self, line_index: usize) -> Cow<'_, str> {
+                self.0.line(line_index)
+            }
+
+            fn insert(&mut self, _position: (usize, usize), _text: &str) {}
+
+            fn remove(&mut self, start: (usize, usize), end: (usize, usize)) -> String {
+                self.0.remove(start, end)
+            }
+        }
+
+        run(|value| IgnoringBuffer(LineBuffer::from(value)));
+    }
+}
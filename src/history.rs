@@ -0,0 +1,141 @@
+//! Undo and redo history for an editor's value.
+
+/// A single reversible change to an editor's value.
+pub(crate) struct Edit {
+    /// The raw (bytes, lines) position at which the change was made.
+    pub(crate) position: (usize, usize),
+
+    /// The text removed at the position, with lines joined by `\n`.
+    pub(crate) removed: String,
+
+    /// The text inserted at the position, with lines joined by `\n`.
+    pub(crate) inserted: String,
+}
+
+/// The kind of action which produced a group of edits, used to decide whether groups coalesce.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum EditKind {
+    /// Characters typed one at a time, which coalesce into a single undo step.
+    Typing,
+
+    /// Any other change, which is always its own undo step.
+    Other,
+}
+
+/// A group of edits which are undone and redone as a single step.
+pub(crate) struct EditGroup {
+    /// The kind of action which produced these edits.
+    pub(crate) kind: EditKind,
+
+    /// The edits in the order they were applied.
+    pub(crate) edits: Vec<Edit>,
+
+    /// The cursor position in (columns, lines) before the edits were applied.
+    pub(crate) cursor_before: (usize, usize),
+
+    /// The selection anchor in (columns, lines) before the edits were applied.
+    pub(crate) anchor_before: Option<(usize, usize)>,
+
+    /// The cursor position in (columns, lines) after the edits were applied.
+    pub(crate) cursor_after: (usize, usize),
+}
+
+/// An editor's undo and redo stacks.
+pub(crate) struct History {
+    /// Groups which may be undone, most recent last.
+    undo: Vec<EditGroup>,
+
+    /// Groups which may be redone, most recently undone last.
+    redo: Vec<EditGroup>,
+
+    /// Edits applied since the last commit which have yet to be grouped.
+    staged: Vec<Edit>,
+
+    /// Whether the most recent group is closed to further coalescing, e.g. after cursor movement.
+    sealed: bool,
+}
+
+impl History {
+    /// Create a new, empty history.
+    pub(crate) fn new() -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            staged: Vec::new(),
+            sealed: true,
+        }
+    }
+
+    /// Stage an applied edit to be included in the next committed group.
+    pub(crate) fn stage(&mut self, edit: Edit) {
+        self.staged.push(edit);
+    }
+
+    /// Group any staged edits into an undo step, coalescing them into the most recent step if both
+    /// are uninterrupted typing. Committing new edits discards any redo history.
+    pub(crate) fn commit(
+        &mut self,
+        kind: EditKind,
+        cursor_before: (usize, usize),
+        anchor_before: Option<(usize, usize)>,
+        cursor_after: (usize, usize),
+    ) {
+        if self.staged.is_empty() {
+            return;
+        }
+
+        let edits = std::mem::take(&mut self.staged);
+        self.redo.clear();
+
+        if let Some(previous) = self.undo.last_mut() {
+            let coalesce = !self.sealed
+                && kind == EditKind::Typing
+                && previous.kind == EditKind::Typing
+                && anchor_before.is_none()
+                && previous.cursor_after == cursor_before;
+
+            if coalesce {
+                previous.edits.extend(edits);
+                previous.cursor_after = cursor_after;
+                return;
+            }
+        }
+
+        self.undo.push(EditGroup {
+            kind,
+            edits,
+            cursor_before,
+            anchor_before,
+            cursor_after,
+        });
+
+        self.sealed = false;
+    }
+
+    /// Prevent the most recent group from coalescing with any subsequent edits.
+    pub(crate) fn seal(&mut self) {
+        self.sealed = true;
+    }
+
+    /// Take the most recent undoable group, if any.
+    pub(crate) fn pop_undo(&mut self) -> Option<EditGroup> {
+        self.sealed = true;
+        self.undo.pop()
+    }
+
+    /// Take the most recently undone group, if any.
+    pub(crate) fn pop_redo(&mut self) -> Option<EditGroup> {
+        self.sealed = true;
+        self.redo.pop()
+    }
+
+    /// Record a group which was just undone so that it may be redone.
+    pub(crate) fn push_redo(&mut self, group: EditGroup) {
+        self.redo.push(group);
+    }
+
+    /// Record a group which was just redone so that it may be undone again.
+    pub(crate) fn push_undo(&mut self, group: EditGroup) {
+        self.undo.push(group);
+    }
+}
@@ -0,0 +1,181 @@
+//! Automatic indentation of new lines.
+
+use crate::{Text, TextBuffer};
+
+/// Automatic indentation configuration for an editor. When [Key::Enter] splits a line, the new
+/// line inherits the leading whitespace preceding the cursor, increased by one level if the text
+/// before the cursor ends with a trigger character.
+///
+/// [Key::Enter]: crate::Key::Enter
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AutoIndent {
+    /// Characters which increase the new line's indentation when they end the text before the
+    /// cursor, ignoring trailing whitespace, e.g. `':'` or `'{'`.
+    pub triggers: Vec<char>,
+
+    /// The indentation added for each level, e.g. four spaces or a tab.
+    pub indent: String,
+}
+
+impl AutoIndent {
+    /// Create a new automatic indentation configuration.
+    pub fn new(triggers: Vec<char>, indent: &str) -> Self {
+        Self {
+            triggers,
+            indent: indent.to_string(),
+        }
+    }
+
+    /// Get the indentation for a new line following the specified text.
+    fn get_indent(&self, prefix: &str) -> String {
+        let content = prefix.trim_start_matches([' ', '\t']);
+        let mut indent = prefix[..prefix.len() - content.len()].to_string();
+
+        let is_triggered = content
+            .trim_end()
+            .chars()
+            .next_back()
+            .is_some_and(|ch| self.triggers.contains(&ch));
+
+        if is_triggered {
+            indent.push_str(&self.indent);
+        }
+
+        indent
+    }
+}
+
+impl Default for AutoIndent {
+    /// Inherit leading whitespace without any trigger characters.
+    fn default() -> Self {
+        Self::new(Vec::new(), "    ")
+    }
+}
+
+impl<B: TextBuffer> Text<B> {
+    /// This editor's automatic indentation configuration, if automatic indentation is enabled.
+    pub fn auto_indent(&self) -> Option<&AutoIndent> {
+        self.auto_indent.as_ref()
+    }
+
+    /// Enable or disable automatic indentation of new lines. It's disabled by default, and list
+    /// continuation takes precedence on list items.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_text::{AutoIndent, Key, Text};
+    ///
+    /// let mut text = Text::from("key:", (4, 0), true);
+    /// text.set_auto_indent(Some(AutoIndent::new(vec![':'], "  ")));
+    ///
+    /// text.handle_input(Key::Enter);
+    /// text.insert_str("nested: a");
+    /// text.handle_input(Key::Enter);
+    ///
+    /// assert_eq!("key:\n  nested: a\n  ", text.value());
+    /// assert_eq!((2, 2), text.cursor());
+    /// ```
+    pub fn set_auto_indent(&mut self, auto_indent: Option<AutoIndent>) {
+        self.auto_indent = auto_indent;
+    }
+
+    /// Get the indentation for a new line inserted after the specified text at the start of a
+    /// line, which is empty if automatic indentation is disabled.
+    pub(crate) fn get_auto_indent(&self, prefix: &str) -> String {
+        self.auto_indent
+            .as_ref()
+            .map_or_else(String::new, |auto_indent| auto_indent.get_indent(prefix))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Key;
+
+    #[test]
+    fn inherits_whitespace() {
+        let auto_indent = AutoIndent::default();
+
+        assert_eq!("", auto_indent.get_indent("abc"));
+        assert_eq!("  ", auto_indent.get_indent("  abc"));
+        assert_eq!("\t ", auto_indent.get_indent("\t abc:"));
+        assert_eq!("   ", auto_indent.get_indent("   "));
+    }
+
+    #[test]
+    fn triggers() {
+        let auto_indent = AutoIndent::new(vec![':', '{'], "\t");
+
+        assert_eq!("\t", auto_indent.get_indent("key:"));
+        assert_eq!("  \t", auto_indent.get_indent("  fn main() { "));
+        assert_eq!("  ", auto_indent.get_indent("  a: b"));
+        assert_eq!("", auto_indent.get_indent(""));
+    }
+
+    #[test]
+    fn enter_inherits_indent() {
+        let mut text = Text::from("    abc", (7, 0), true);
+        text.set_auto_indent(Some(AutoIndent::default()));
+
+        text.handle_input(Key::Enter);
+
+        assert_eq!("    abc\n    ", text.value());
+        assert_eq!((4, 1), text.cursor());
+    }
+
+    #[test]
+    fn enter_mid_line() {
+        let mut text = Text::from("  if x {y}", (8, 0), true);
+        text.set_auto_indent(Some(AutoIndent::new(vec!['{'], "  ")));
+
+        text.handle_input(Key::Enter);
+
+        assert_eq!("  if x {\n    y}", text.value());
+        assert_eq!((4, 1), text.cursor());
+    }
+
+    #[test]
+    fn enter_within_indentation() {
+        let mut text = Text::from("    abc", (2, 0), true);
+        text.set_auto_indent(Some(AutoIndent::default()));
+
+        text.handle_input(Key::Enter);
+
+        assert_eq!("  \n    abc", text.value());
+        assert_eq!((2, 1), text.cursor());
+    }
+
+    #[test]
+    fn list_continuation_precedence() {
+        let mut text = Text::from("  - a:", (6, 0), true);
+        text.set_auto_indent(Some(AutoIndent::new(vec![':'], "  ")));
+
+        text.handle_input(Key::Enter);
+
+        assert_eq!("  - a:\n  - ", text.value());
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        let mut text = Text::from("  abc", (5, 0), true);
+
+        text.handle_input(Key::Enter);
+
+        assert_eq!("  abc\n", text.value());
+        assert_eq!(None, text.auto_indent());
+    }
+
+    #[test]
+    fn undo() {
+        let mut text = Text::from("a:", (2, 0), true);
+        text.set_auto_indent(Some(AutoIndent::new(vec![':'], "  ")));
+
+        text.handle_input(Key::Enter);
+        assert_eq!("a:\n  ", text.value());
+
+        text.handle_input(Key::Undo);
+        assert_eq!("a:", text.value());
+        assert_eq!((2, 0), text.cursor());
+    }
+}
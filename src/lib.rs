@@ -26,17 +26,100 @@
 //! assert_eq!((14, 0), text.cursor());
 //! ```
 
+use std::borrow::Cow;
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+mod buffer;
+pub use buffer::{LineBuffer, Lines, RopeBuffer, TextBuffer};
+
+pub mod conformance;
+
+mod clipboard;
+pub use clipboard::{Clipboard, MemoryClipboard};
+
+mod history;
+use history::{Edit, EditKind, History};
+
+mod indent;
+pub use indent::AutoIndent;
+
+mod list;
+pub use list::{ListContinuation, ListMarker};
+
+mod offset;
+pub use offset::OffsetUnit;
+
+mod position;
+pub use position::{Position, Range};
+
+mod reflow;
+
+mod search;
+pub use regex::Regex;
+use search::IncrementalSearch;
+pub use search::SearchOptions;
+
+mod viewport;
+pub use viewport::Viewport;
+
+mod wrap;
+pub use wrap::{SoftWrap, WrapMode};
+
 pub enum Key {
     Char(char),
     Backspace,
+    /// Delete the character following the cursor.
+    Delete,
     Enter,
     Up,
     Down,
     Left,
     Right,
+    /// Extend the selection up one line, i.e. Shift+Up.
+    SelectUp,
+    /// Extend the selection down one line, i.e. Shift+Down.
+    SelectDown,
+    /// Extend the selection left one character, i.e. Shift+Left.
+    SelectLeft,
+    /// Extend the selection right one character, i.e. Shift+Right.
+    SelectRight,
+    /// Undo the most recent edit, e.g. Ctrl+Z.
+    Undo,
+    /// Redo the most recently undone edit, e.g. Ctrl+Y.
+    Redo,
+    /// Move to the start of the previous word, e.g. Ctrl+Left.
+    WordLeft,
+    /// Move to the end of the next word, e.g. Ctrl+Right.
+    WordRight,
+    /// Delete back to the start of the previous word, e.g. Ctrl+W or Ctrl+Backspace.
+    DeleteWordBackward,
+    /// Delete forward to the end of the next word, e.g. Ctrl+Delete.
+    DeleteWordForward,
+    /// Move to the line's first non-whitespace character, or to its start if already there.
+    Home,
+    /// Move to the line's end.
+    End,
+    /// Move to the start of the value, e.g. Ctrl+Home.
+    DocumentStart,
+    /// Move to the end of the value, e.g. Ctrl+End.
+    DocumentEnd,
+    /// Move up by the configured page height.
+    PageUp,
+    /// Move down by the configured page height.
+    PageDown,
+    /// Insert a block of pasted text literally, e.g. from a terminal's bracketed paste, without
+    /// the list continuation or automatic indentation applied by [Key::Enter].
+    Paste(String),
+    /// Start an incremental search, e.g. Ctrl+F. While searching, move to the next match.
+    Search,
+    /// Cancel an incremental search, restoring the cursor, or otherwise clear the selection.
+    Escape,
 }
 
-/// A multi-line text editor with cursor management capabilities.
+/// A multi-line text editor with cursor management capabilities. The editor's value is stored in
+/// a [TextBuffer], which is a [LineBuffer] by default.
 ///
 /// # Examples
 /// ## Single-line mode
@@ -51,9 +134,9 @@ pub enum Key {
 ///
 /// assert_eq!((2, 0), text.cursor());
 /// assert_eq!("ab", text.value());
-/// assert_eq!(&vec![
+/// assert_eq!(vec![
 ///     "ab".to_string(),
-/// ], text.lines());
+/// ], text.lines().collect::<Vec<_>>());
 /// ```
 /// ## Multi-line mode
 /// ```
@@ -64,26 +147,55 @@ pub enum Key {
 /// text.handle_input(Key::Char('a'));
 /// text.handle_input(Key::Enter);
 /// text.handle_input(Key::Char('b'));
-
+///
 /// assert_eq!((1, 1), text.cursor());
 /// assert_eq!("a\nb", text.value());
-/// assert_eq!(&vec![
+/// assert_eq!(vec![
 ///     "a".to_string(),
 ///     "b".to_string(),
-/// ], text.lines());
+/// ], text.lines().collect::<Vec<_>>());
 /// ```
-pub struct Text {
+pub struct Text<B: TextBuffer = LineBuffer> {
     /// The lines that comprise this editor's value.
-    lines: Vec<String>,
+    buffer: B,
 
-    /// The cursor's position in the editor in (columns, lines).
+    /// The cursor's position in the editor in (columns, lines). Columns are measured in grapheme
+    /// clusters rather than bytes or characters.
     cursor: (usize, usize),
 
     /// Whether this editor is configured for multi-line value editing.
     multi_line: bool,
 
-    /// The preferred position to use when restoring across vertical movements.
+    /// The preferred position to use when restoring across vertical movements. While soft
+    /// wrapping, this is a display column within a visual row.
     preferred_column: usize,
+
+    /// The selection's anchor in (columns, lines), if any. The cursor is the selection's head.
+    anchor: Option<(usize, usize)>,
+
+    /// The history of edits made to this editor's value, for undo and redo.
+    history: History,
+
+    /// The number of lines moved by page motions, typically the height of the rendered viewport.
+    page_height: usize,
+
+    /// This editor's soft wrapping configuration, if enabled.
+    soft_wrap: Option<SoftWrap>,
+
+    /// The clipboard used for cut, copy and paste.
+    clipboard: Box<dyn Clipboard>,
+
+    /// The incremental search in progress, if any.
+    search: Option<IncrementalSearch>,
+
+    /// The options used by incremental searches.
+    search_options: SearchOptions,
+
+    /// This editor's list continuation rules, if enabled.
+    list_continuation: Option<ListContinuation>,
+
+    /// This editor's automatic indentation configuration, if enabled.
+    auto_indent: Option<AutoIndent>,
 }
 
 impl Text {
@@ -105,15 +217,12 @@ impl Text {
     /// assert_eq!("abcd", text.value());
     /// ```
     pub fn new(multi_line: bool) -> Self {
-        Self {
-            lines: vec![String::new()],
-            cursor: (0, 0),
-            multi_line,
-            preferred_column: 0,
-        }
+        Self::with_buffer(LineBuffer::new(), (0, 0), multi_line)
     }
 
     /// Create a new editor from the specified value and cursor state and in the specified mode.
+    /// Lines may be separated by `\n`, `\r\n` or `\r`, and line breaks are removed in single-line
+    /// mode.
     ///
     /// # Examples
     /// ## Multi-line value and mode
@@ -124,10 +233,10 @@ impl Text {
     ///
     /// assert_eq!("Hello,\nworld!", text.value());
     /// assert_eq!((2, 1), text.cursor());
-    /// assert_eq!(&vec![
+    /// assert_eq!(vec![
     ///     "Hello,".to_string(),
     ///     "world!".to_string(),
-    /// ], text.lines());
+    /// ], text.lines().collect::<Vec<_>>());
     /// ```
     /// ## Multi-line value collapsed by single-line mode
     /// ```
@@ -137,110 +246,536 @@ impl Text {
     ///
     /// assert_eq!("Hello, world!", text.value());
     /// assert_eq!((7, 0), text.cursor());
-    /// assert_eq!(&vec![
+    /// assert_eq!(vec![
     ///     "Hello, world!".to_string(),
-    /// ], text.lines());
+    /// ], text.lines().collect::<Vec<_>>());
     /// ```
-    pub fn from(value: &str, cursor: (usize, usize), multi_line: bool) -> Self {
-        let mut lines = if multi_line {
-            value.lines().map(|line| line.to_string()).collect()
-        } else {
-            vec![value.replace("\n", "").replace("\r", "")]
-        };
+    pub fn from(value: &str, cursor: impl Into<Position>, multi_line: bool) -> Self {
+        let value = normalize_newlines(value, multi_line);
 
-        if lines.is_empty() || value.ends_with("\n") || value.ends_with("\r\n") {
-            lines.push(String::new());
-        }
+        Self::with_buffer(LineBuffer::from(value.as_str()), cursor, multi_line)
+    }
+}
 
+impl<B: TextBuffer> Text<B> {
+    /// Create a new editor over the specified buffer with the specified cursor state and in the
+    /// specified mode. In single-line mode, the buffer's lines are joined into one.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_text::{Key, RopeBuffer, Text};
+    ///
+    /// let mut text = Text::with_buffer(RopeBuffer::from("Hello,\nworld!"), (6, 0), true);
+    ///
+    /// text.handle_input(Key::Enter);
+    ///
+    /// assert_eq!(vec!["Hello,", "", "world!"], text.lines().collect::<Vec<_>>());
+    /// ```
+    pub fn with_buffer(buffer: B, cursor: impl Into<Position>, multi_line: bool) -> Self {
         let mut text = Self {
-            lines,
+            buffer,
             cursor: (0, 0),
             multi_line,
             preferred_column: 0,
+            anchor: None,
+            history: History::new(),
+            page_height: 1,
+            soft_wrap: None,
+            clipboard: Box::new(MemoryClipboard::new()),
+            search: None,
+            search_options: SearchOptions::default(),
+            list_continuation: Some(ListContinuation::default()),
+            auto_indent: None,
         };
 
+        if !multi_line {
+            while text.buffer.line_count() > 1 {
+                let line_end = text.buffer.line_len(0);
+                text.buffer.remove((line_end, 0), (0, 1));
+            }
+        }
+
         text.set_cursor(cursor);
 
         text
     }
 
-    /// This editor's current cursor position as (columns, lines).
-    pub fn cursor(&self) -> (usize, usize) {
-        self.cursor
+    /// The buffer this editor's value is stored in.
+    pub fn buffer(&self) -> &B {
+        &self.buffer
+    }
+
+    /// This editor's current cursor position.
+    pub fn cursor(&self) -> Position {
+        self.cursor.into()
+    }
+
+    /// This editor's current cursor position as (display columns, lines). Display columns account
+    /// for wide characters occupying two terminal cells and zero-width characters occupying none,
+    /// so this is the position a renderer should place the terminal's cursor at.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_text::Text;
+    ///
+    /// let text = Text::from("日本語", (2, 0), false);
+    ///
+    /// assert_eq!((2, 0), text.cursor());
+    /// assert_eq!((4, 0), text.display_cursor());
+    /// ```
+    pub fn display_cursor(&self) -> (usize, usize) {
+        let line = self.buffer.line(self.cursor.1);
+        let prefix = &line[..get_byte_index(&line, self.cursor.0)];
+
+        (get_display_width(prefix), self.cursor.1)
+    }
+
+    /// The specified line's width in terminal display columns.
+    ///
+    /// # Panics
+    /// If the line index is out of bounds.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_text::Text;
+    ///
+    /// let text = Text::from("abc\n日本語", (0, 0), true);
+    ///
+    /// assert_eq!(3, text.line_display_width(0));
+    /// assert_eq!(6, text.line_display_width(1));
+    /// ```
+    pub fn line_display_width(&self, line_index: usize) -> usize {
+        get_display_width(&self.buffer.line(line_index))
     }
 
-    /// This editor's current value.
+    /// This editor's current value. This joins every line into a new string; prefer
+    /// [Text::lines] or [Text::line] to read part of a large value.
     pub fn value(&self) -> String {
-        self.lines.join("\n")
+        self.buffer.value()
+    }
+
+    /// An iterator over this editor's value's lines, excluding line breaks. Lines are borrowed
+    /// from the editor where its buffer allows.
+    pub fn lines(&self) -> Lines<'_, B> {
+        Lines::new(&self.buffer)
+    }
+
+    /// The specified line's content, excluding its line break.
+    ///
+    /// # Panics
+    /// If the line index is out of bounds.
+    pub fn line(&self, line_index: usize) -> Cow<'_, str> {
+        self.buffer.line(line_index)
+    }
+
+    /// The number of lines in this editor's value, which is always at least one.
+    pub fn line_count(&self) -> usize {
+        self.buffer.line_count()
+    }
+
+    /// Update this editor's cursor position, clearing any selection. The position will be clamped
+    /// to the editor's current value.
+    pub fn set_cursor(&mut self, position: impl Into<Position>) {
+        self.cursor = self.clamp_position(position.into().into());
+        self.anchor = None;
+        self.history.seal();
+
+        self.update_preferred_column();
     }
 
-    /// This editor's value's lines.
-    pub fn lines(&self) -> &Vec<String> {
-        &self.lines
+    /// This editor's selected range, ordered start to end. Returns `None` if nothing is selected.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_text::{Text, Key, Range};
+    ///
+    /// let mut text = Text::from("Hello,\nworld!", (3, 0), true);
+    ///
+    /// text.handle_input(Key::SelectDown);
+    /// text.handle_input(Key::SelectLeft);
+    ///
+    /// assert_eq!(Some(Range::new((3, 0), (2, 1))), text.selection());
+    /// assert_eq!(Some("lo,\nwo".to_string()), text.selected_text());
+    /// ```
+    pub fn selection(&self) -> Option<Range> {
+        let anchor = self.anchor?;
+        if anchor == self.cursor {
+            return None;
+        }
+
+        Some(Range::new(anchor, self.cursor))
     }
 
-    /// Update this editor's cursor position. The position will be clamped to the editor's current
-    /// value.
-    pub fn set_cursor(&mut self, position: (usize, usize)) {
-        self.cursor = position;
+    /// This editor's selected text, if anything is selected. Lines are joined by `\n`.
+    pub fn selected_text(&self) -> Option<String> {
+        let selection = self.selection()?;
+        let start = self.get_raw_position(selection.start.into());
+        let end = self.get_raw_position(selection.end.into());
 
-        // Clamp the line
-        if self.cursor.1 >= self.lines.len() {
-            self.cursor.1 = self.lines.len() - 1;
+        if start.1 == end.1 {
+            return Some(self.buffer.line(start.1)[start.0..end.0].to_string());
         }
 
-        // Clamp the column
-        let line_length = self.get_line_length(self.cursor.1);
-        if self.cursor.0 > line_length {
-            self.cursor.0 = line_length;
+        let mut text = self.buffer.line(start.1)[start.0..].to_string();
+        for line in self.lines().take(end.1).skip(start.1 + 1) {
+            text.push('\n');
+            text.push_str(&line);
         }
+        text.push('\n');
+        text.push_str(&self.buffer.line(end.1)[..end.0]);
+
+        Some(text)
+    }
+
+    /// Select the text between the specified anchor and cursor positions, leaving the cursor at
+    /// the latter. Both positions will be clamped to the editor's current value.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_text::{Text, Key};
+    ///
+    /// let mut text = Text::from("Hello, world!", (0, 0), false);
+    ///
+    /// text.set_selection((7, 0), (12, 0));
+    /// text.handle_input(Key::Char('W'));
+    ///
+    /// assert_eq!("Hello, W!", text.value());
+    /// assert_eq!((8, 0), text.cursor());
+    /// ```
+    pub fn set_selection(&mut self, anchor: impl Into<Position>, cursor: impl Into<Position>) {
+        self.set_cursor(cursor);
+        self.anchor = Some(self.clamp_position(anchor.into().into()));
+    }
+
+    /// Clear this editor's selection, if any, leaving the cursor in place.
+    pub fn clear_selection(&mut self) {
+        self.anchor = None;
+    }
+
+    /// The number of lines moved by [Key::PageUp] and [Key::PageDown].
+    pub fn page_height(&self) -> usize {
+        self.page_height
+    }
+
+    /// Update the number of lines moved by [Key::PageUp] and [Key::PageDown], typically to the
+    /// height of the viewport this editor is rendered in. The height is at least one line.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_text::{Text, Key};
+    ///
+    /// let mut text = Text::from("a\nb\nc\nd\ne", (0, 0), true);
+    /// text.set_page_height(3);
+    ///
+    /// text.handle_input(Key::PageDown);
+    /// assert_eq!((0, 3), text.cursor());
+    ///
+    /// text.handle_input(Key::PageDown);
+    /// assert_eq!((0, 4), text.cursor());
+    /// ```
+    pub fn set_page_height(&mut self, page_height: usize) {
+        self.page_height = std::cmp::max(page_height, 1);
+    }
+
+    /// Insert the specified text at the cursor, replacing any selection, as a single undoable
+    /// edit. In multi-line mode, the text is split into lines on `\n`, `\r\n` and `\r`; in
+    /// single-line mode, line breaks are removed as by [Text::from].
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_text::Text;
+    ///
+    /// let mut text = Text::from("ad", (1, 0), true);
+    ///
+    /// text.insert_str("b\r\nc");
+    ///
+    /// assert_eq!("ab\ncd", text.value());
+    /// assert_eq!((1, 1), text.cursor());
+    ///
+    /// text.undo();
+    /// assert_eq!("ad", text.value());
+    /// ```
+    pub fn insert_str(&mut self, value: &str) {
+        let value = normalize_newlines(value, self.multi_line);
+
+        self.transact(EditKind::Other, |text| {
+            text.delete_selection();
+
+            let position = text.get_raw_position(text.cursor);
+            let end = text.splice(position, position, &value);
+            text.cursor = text.get_position(end);
+
+            text.update_preferred_column();
+        });
+    }
+
+    /// Delete the text between the specified positions as a single undoable edit.
+    /// The cursor and selection are adjusted as by [Text::replace_range].
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_text::Text;
+    ///
+    /// let mut text = Text::from("Hello,\nworld!", (3, 1), true);
+    ///
+    /// text.delete_range((5, 0), (0, 1));
+    ///
+    /// assert_eq!("Helloworld!", text.value());
+    /// assert_eq!((8, 0), text.cursor());
+    /// ```
+    pub fn delete_range(&mut self, start: impl Into<Position>, end: impl Into<Position>) {
+        self.replace_range(start, end, "");
+    }
+
+    /// Replace the text between the specified positions with the specified text
+    /// as a single undoable edit. Line breaks in the text are handled as by [Text::insert_str],
+    /// and the positions will be clamped to the editor's current value.
+    ///
+    /// The cursor and selection anchor are adjusted to stay with the surrounding text: positions
+    /// before the range are unchanged, positions within or at the end of the range move to the
+    /// end of the replacement, and positions after the range shift by the change in length.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_text::Text;
+    ///
+    /// let mut text = Text::from("teh cat", (7, 0), false);
+    ///
+    /// text.replace_range((0, 0), (3, 0), "the");
+    /// assert_eq!("the cat", text.value());
+    /// assert_eq!((7, 0), text.cursor());
+    ///
+    /// text.replace_range((4, 0), (7, 0), "big dog");
+    /// assert_eq!("the big dog", text.value());
+    /// assert_eq!((11, 0), text.cursor());
+    /// ```
+    pub fn replace_range(
+        &mut self,
+        start: impl Into<Position>,
+        end: impl Into<Position>,
+        value: &str,
+    ) {
+        let value = normalize_newlines(value, self.multi_line);
+        let (start, end) = (start.into().into(), end.into().into());
+
+        self.transact(EditKind::Other, |text| {
+            text.replace_range_adjusting(start, end, &value);
+        });
+    }
+
+    /// Replace this editor's entire value as a single undoable edit, keeping the cursor's position
+    /// clamped to the new value and clearing any selection. Line breaks are handled as by
+    /// [Text::from].
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_text::Text;
+    ///
+    /// let mut text = Text::from("Hello,\nworld!", (3, 1), true);
+    ///
+    /// text.set_value("Goodbye,\nmoon!");
+    /// assert_eq!("Goodbye,\nmoon!", text.value());
+    /// assert_eq!((3, 1), text.cursor());
+    ///
+    /// text.undo();
+    /// assert_eq!("Hello,\nworld!", text.value());
+    /// ```
+    pub fn set_value(&mut self, value: &str) {
+        let value = normalize_newlines(value, self.multi_line);
+
+        let last_line = self.buffer.line_count() - 1;
+        let end = (self.get_line_length(last_line), last_line);
+
+        self.transact(EditKind::Other, |text| {
+            let cursor = text.cursor;
+            text.anchor = None;
 
-        self.preferred_column = self.cursor.0;
+            text.replace_range_adjusting((0, 0), end, &value);
+
+            text.cursor = text.clamp_position(cursor);
+            text.update_preferred_column();
+        });
     }
 
-    /// Update this editor's state from the specified input.
+    /// Update this editor's state from the specified input. During an incremental search, input
+    /// edits the search query instead, as by [Key::Search].
     pub fn handle_input(&mut self, input: Key) {
+        let Some(input) = self.handle_search_input(input) else {
+            return;
+        };
+
         match input {
             Key::Char(ch) => self.insert_character(ch),
             Key::Backspace => self.backspace_character(),
+            Key::Delete => self.delete_character(),
             Key::Enter => self.insert_newline(),
-            Key::Up => self.move_up(),
-            Key::Down => self.move_down(),
-            Key::Left => self.move_left(),
-            Key::Right => self.move_right(),
+            Key::Up => self.move_cursor(Self::move_up),
+            Key::Down => self.move_cursor(Self::move_down),
+            Key::Left => match self.selection() {
+                Some(selection) => self.set_cursor(selection.start),
+                None => self.move_cursor(Self::move_left),
+            },
+            Key::Right => match self.selection() {
+                Some(selection) => self.set_cursor(selection.end),
+                None => self.move_cursor(Self::move_right),
+            },
+            Key::SelectUp => self.extend_selection(Self::move_up),
+            Key::SelectDown => self.extend_selection(Self::move_down),
+            Key::SelectLeft => self.extend_selection(Self::move_left),
+            Key::SelectRight => self.extend_selection(Self::move_right),
+            Key::Undo => {
+                self.undo();
+            }
+            Key::Redo => {
+                self.redo();
+            }
+            Key::WordLeft => self.move_cursor(Self::move_word_left),
+            Key::WordRight => self.move_cursor(Self::move_word_right),
+            Key::DeleteWordBackward => self.delete_word_backward(),
+            Key::DeleteWordForward => self.delete_word_forward(),
+            Key::Home => self.move_cursor(Self::move_home),
+            Key::End => self.move_cursor(Self::move_end),
+            Key::DocumentStart => self.move_cursor(Self::move_document_start),
+            Key::DocumentEnd => self.move_cursor(Self::move_document_end),
+            Key::PageUp => self.move_cursor(Self::move_page_up),
+            Key::PageDown => self.move_cursor(Self::move_page_down),
+            Key::Paste(value) => self.insert_str(&value),
+            Key::Search => self.start_search(),
+            Key::Escape => self.clear_selection(),
+        }
+    }
+
+    /// Replace the text between the specified (columns, lines) positions with the specified
+    /// normalized text, adjusting the cursor and selection anchor as by [Text::replace_range].
+    fn replace_range_adjusting(&mut self, start: (usize, usize), end: (usize, usize), value: &str) {
+        let (start, end) = order_positions(self.clamp_position(start), self.clamp_position(end));
+        let (start, end) = (self.get_raw_position(start), self.get_raw_position(end));
+
+        let cursor = self.get_raw_position(self.cursor);
+        let anchor = self.anchor.map(|anchor| self.get_raw_position(anchor));
+
+        let new_end = self.splice(start, end, value);
+
+        let cursor = get_adjusted_raw_position(cursor, start, end, new_end);
+        self.cursor = self.get_position(cursor);
+
+        self.anchor = anchor.map(|anchor| {
+            let anchor = get_adjusted_raw_position(anchor, start, end, new_end);
+            self.get_position(anchor)
+        });
+
+        self.update_preferred_column();
+    }
+
+    /// Move the editor's cursor using the specified motion, discarding any selection.
+    fn move_cursor(&mut self, motion: fn(&mut Self)) {
+        self.anchor = None;
+        self.history.seal();
+        motion(self);
+    }
+
+    /// Move the editor's cursor using the specified motion, extending the selection from its
+    /// anchor or starting a new selection from the cursor's current position.
+    fn extend_selection(&mut self, motion: fn(&mut Self)) {
+        if self.anchor.is_none() {
+            self.anchor = Some(self.cursor);
         }
+
+        self.history.seal();
+        motion(self);
+    }
+
+    /// Delete the selected text, if any, moving the cursor to the selection's start. Returns
+    /// whether anything was deleted.
+    fn delete_selection(&mut self) -> bool {
+        let selection = self.selection();
+        self.anchor = None;
+
+        let Some(selection) = selection else {
+            return false;
+        };
+
+        let (start, end) = (selection.start.into(), selection.end.into());
+        self.splice(self.get_raw_position(start), self.get_raw_position(end), "");
+        self.cursor = start;
+        self.update_preferred_column();
+
+        true
     }
 
     /// Insert the specified character at the editor's current cursor position.
     fn insert_character(&mut self, ch: char) {
-        self.lines[self.cursor.1].insert(self.cursor.0, ch);
-        self.cursor.0 += 1;
+        // Line breaks split the line in multi-line mode and are dropped in single-line mode
+        if ch == '\n' || ch == '\r' {
+            self.insert_newline();
+            return;
+        }
+
+        self.transact(EditKind::Typing, |text| {
+            text.delete_selection();
 
-        self.preferred_column = self.cursor.0;
+            // The character may combine with the preceding grapheme, e.g. a combining mark
+            let position = text.get_raw_position(text.cursor);
+            let end = text.splice(position, position, ch.encode_utf8(&mut [0; 4]));
+            text.cursor = text.get_position(end);
+
+            text.update_preferred_column();
+        });
     }
 
     /// Backspace the character preceding the editor's current cursor position.
     fn backspace_character(&mut self) {
-        let at_start_of_line = self.cursor.0 == 0;
-        if at_start_of_line {
-            let on_first_line = self.cursor.1 == 0;
-            if !on_first_line {
-                // Remove the current line
-                let line = self.lines.remove(self.cursor.1);
+        self.transact(EditKind::Other, |text| {
+            if text.delete_selection() {
+                return;
+            }
 
-                // Move the cursor to the end of the previous line
-                let prior_line_index = self.cursor.1 - 1;
-                self.cursor = (self.get_line_length(prior_line_index), prior_line_index);
+            let at_start_of_line = text.cursor.0 == 0;
+            if at_start_of_line {
+                let on_first_line = text.cursor.1 == 0;
+                if !on_first_line {
+                    // Join the current line onto the end of the previous line
+                    let prior_line_index = text.cursor.1 - 1;
+                    let prior_line_end = (text.buffer.line_len(prior_line_index), prior_line_index);
+                    text.splice(prior_line_end, (0, text.cursor.1), "");
+
+                    text.cursor = text.get_position(prior_line_end);
+                }
+            } else {
+                let start = text.get_raw_position((text.cursor.0 - 1, text.cursor.1));
+                let end = text.get_raw_position(text.cursor);
+                text.splice(start, end, "");
+
+                text.cursor.0 -= 1;
+            }
+
+            text.update_preferred_column();
+        });
+    }
 
-                // Append the just-deleted line after the cursor in the previous line
-                self.lines[self.cursor.1].push_str(&line);
+    /// Delete the character following the editor's current cursor position.
+    fn delete_character(&mut self) {
+        self.transact(EditKind::Other, |text| {
+            if text.delete_selection() {
+                return;
+            }
+
+            let at_end_of_line = text.cursor.0 == text.get_line_length(text.cursor.1);
+            if at_end_of_line {
+                let on_last_line = text.cursor.1 + 1 == text.buffer.line_count();
+                if !on_last_line {
+                    // Join the next line onto the end of the current line
+                    let line_end = (text.buffer.line_len(text.cursor.1), text.cursor.1);
+                    text.splice(line_end, (0, text.cursor.1 + 1), "");
+                }
+            } else {
+                let start = text.get_raw_position(text.cursor);
+                let end = text.get_raw_position((text.cursor.0 + 1, text.cursor.1));
+                text.splice(start, end, "");
             }
-        } else {
-            self.cursor.0 -= 1;
-            self.lines[self.cursor.1].remove(self.cursor.0);
-        }
 
-        self.preferred_column = self.cursor.0;
+            text.update_preferred_column();
+        });
     }
 
     /// Insert a newline at the editor's current cursor position.
@@ -249,27 +784,183 @@ impl Text {
             return;
         }
 
-        // Split the current line at the cursor
-        let (prefix, suffix) = self.lines[self.cursor.1].split_at(self.cursor.0).to_owned();
-        let (prefix, suffix) = (prefix.to_string(), suffix.to_string());
+        self.transact(EditKind::Other, |text| {
+            text.delete_selection();
+
+            // Split the current line at the cursor, continuing the line's list item if it has one
+            // or otherwise its indentation
+            let position = text.get_raw_position(text.cursor);
+            let line = text.buffer.line(position.1);
+            let item = text.get_list_item(&line, position.0);
+            let indent = text.get_auto_indent(&line[..position.0]);
+            let line_end = (line.len(), position.1);
+
+            let end = match item {
+                // An item without content ends the list, removing the item's marker
+                Some(item) if !item.has_content => text.splice((0, position.1), line_end, ""),
+                Some(item) => text.splice(position, position, &format!("\n{}", item.continuation)),
+                None => text.splice(position, position, &format!("\n{indent}")),
+            };
+            text.cursor = text.get_position(end);
+
+            text.update_preferred_column();
+        });
+    }
+
+    /// Delete from the start of the word preceding the editor's cursor up to the cursor. At the
+    /// start of a line, this joins the line onto the previous one.
+    fn delete_word_backward(&mut self) {
+        self.transact(EditKind::Other, |text| {
+            if text.delete_selection() {
+                return;
+            }
+
+            let start = text.get_word_left();
+            if start == text.cursor {
+                return;
+            }
+
+            text.splice(
+                text.get_raw_position(start),
+                text.get_raw_position(text.cursor),
+                "",
+            );
+
+            text.cursor = start;
+            text.update_preferred_column();
+        });
+    }
+
+    /// Delete from the editor's cursor up to the end of the word following it. At the end of a
+    /// line, this joins the next line onto the current one.
+    fn delete_word_forward(&mut self) {
+        self.transact(EditKind::Other, |text| {
+            if text.delete_selection() {
+                return;
+            }
+
+            let end = text.get_word_right();
+            if end == text.cursor {
+                return;
+            }
+
+            text.splice(
+                text.get_raw_position(text.cursor),
+                text.get_raw_position(end),
+                "",
+            );
+
+            text.update_preferred_column();
+        });
+    }
+
+    /// Undo the most recent group of edits, restoring the cursor and selection from before them.
+    /// Returns whether there was anything to undo.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_text::{Text, Key};
+    ///
+    /// let mut text = Text::from("Hello", (5, 0), true);
+    ///
+    /// text.handle_input(Key::Char(','));
+    /// text.handle_input(Key::Char(' '));
+    /// text.handle_input(Key::Char('w'));
+    /// text.handle_input(Key::Enter);
+    ///
+    /// assert!(text.undo());
+    /// assert_eq!("Hello, w", text.value());
+    ///
+    /// assert!(text.undo());
+    /// assert_eq!("Hello", text.value());
+    /// assert_eq!((5, 0), text.cursor());
+    ///
+    /// assert!(!text.undo());
+    /// ```
+    pub fn undo(&mut self) -> bool {
+        let Some(group) = self.history.pop_undo() else {
+            return false;
+        };
+
+        for edit in group.edits.iter().rev() {
+            let end = get_raw_end(edit.position, &edit.inserted);
+            self.remove_raw(edit.position, end);
+            self.insert_raw(edit.position, &edit.removed);
+        }
+
+        self.cursor = group.cursor_before;
+        self.anchor = group.anchor_before;
+        self.update_preferred_column();
+
+        self.history.push_redo(group);
+
+        true
+    }
+
+    /// Redo the most recently undone group of edits. Returns whether there was anything to redo.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_text::{Text, Key};
+    ///
+    /// let mut text = Text::from("ab", (2, 0), true);
+    ///
+    /// text.handle_input(Key::Backspace);
+    /// text.handle_input(Key::Undo);
+    /// assert_eq!("ab", text.value());
+    ///
+    /// text.handle_input(Key::Redo);
+    /// assert_eq!("a", text.value());
+    /// assert_eq!((1, 0), text.cursor());
+    /// ```
+    pub fn redo(&mut self) -> bool {
+        let Some(group) = self.history.pop_redo() else {
+            return false;
+        };
+
+        for edit in &group.edits {
+            let end = get_raw_end(edit.position, &edit.removed);
+            self.remove_raw(edit.position, end);
+            self.insert_raw(edit.position, &edit.inserted);
+        }
+
+        self.cursor = group.cursor_after;
+        self.anchor = None;
+        self.update_preferred_column();
+
+        self.history.push_undo(group);
+
+        true
+    }
+
+    /// Perform the specified editing action, grouping any edits it makes into one undo step.
+    fn transact(&mut self, kind: EditKind, action: impl FnOnce(&mut Self)) {
+        let (cursor_before, anchor_before) = (self.cursor, self.anchor);
 
-        // Shorten the current line to the content preceding the cursor
-        self.lines[self.cursor.1] = prefix;
+        action(self);
 
-        // Insert a new line after the current one with the content after the cursor
-        let new_line_index = self.cursor.1 + 1;
-        self.lines.insert(new_line_index, suffix);
+        self.history
+            .commit(kind, cursor_before, anchor_before, self.cursor);
+    }
 
-        // Move the cursor to the start of the next line
-        self.cursor = (0, new_line_index);
+    /// Replace the text between the specified raw (bytes, lines) positions with the specified
+    /// text, recording the change in history. Returns the raw position after the inserted text.
+    /// Splices which change nothing aren't recorded, so they never use up an undo step.
+    fn splice(&mut self, start: (usize, usize), end: (usize, usize), text: &str) -> (usize, usize) {
+        let removed = self.remove_raw(start, end);
+        let inserted_end = self.insert_raw(start, text);
 
-        // If the previous line started with a bullet, continue it
-        if self.lines[self.cursor.1 - 1].starts_with(" - ") {
-            self.lines[new_line_index].insert_str(0, " - ");
-            self.cursor.0 += 3;
+        if removed.is_empty() && text.is_empty() {
+            return inserted_end;
         }
 
-        self.preferred_column = self.cursor.0;
+        self.history.stage(Edit {
+            position: start,
+            removed,
+            inserted: text.to_string(),
+        });
+
+        inserted_end
     }
 
     /// Attempt to move the editor's cursor up one line.
@@ -278,13 +969,14 @@ impl Text {
             return;
         }
 
+        if self.soft_wrap.is_some() {
+            self.move_visual_rows(-1);
+            return;
+        }
+
         let on_first_line = self.cursor.1 == 0;
         if !on_first_line {
-            let previous_line = self.cursor.1 - 1;
-            let desired_column = std::cmp::max(self.cursor.0, self.preferred_column);
-            let new_column = std::cmp::min(desired_column, self.get_line_length(previous_line));
-
-            self.cursor = (new_column, previous_line);
+            self.move_to_line(self.cursor.1 - 1);
         }
     }
 
@@ -294,270 +986,1487 @@ impl Text {
             return;
         }
 
+        if self.soft_wrap.is_some() {
+            self.move_visual_rows(1);
+            return;
+        }
+
         let next_line = self.cursor.1 + 1;
 
-        let is_last_line = next_line == self.lines.len();
+        let is_last_line = next_line == self.buffer.line_count();
         if !is_last_line {
-            let desired_column = std::cmp::max(self.cursor.0, self.preferred_column);
-            let new_column = std::cmp::min(desired_column, self.get_line_length(next_line));
-            self.cursor = (new_column, self.cursor.1 + 1);
+            self.move_to_line(next_line);
+        }
+    }
+
+    /// Attempt to move the editor's cursor up one page.
+    fn move_page_up(&mut self) {
+        if !self.multi_line {
+            return;
         }
+
+        if self.soft_wrap.is_some() {
+            self.move_visual_rows(-self.get_page_rows());
+            return;
+        }
+
+        self.move_to_line(self.cursor.1.saturating_sub(self.page_height));
+    }
+
+    /// Attempt to move the editor's cursor down one page.
+    fn move_page_down(&mut self) {
+        if !self.multi_line {
+            return;
+        }
+
+        if self.soft_wrap.is_some() {
+            self.move_visual_rows(self.get_page_rows());
+            return;
+        }
+
+        let last_line = self.buffer.line_count() - 1;
+        self.move_to_line(std::cmp::min(self.cursor.1 + self.page_height, last_line));
+    }
+
+    /// Get the number of visual rows moved by page motions while soft wrapping.
+    fn get_page_rows(&self) -> isize {
+        isize::try_from(self.page_height).unwrap_or(isize::MAX)
+    }
+
+    /// Move the editor's cursor to the specified line, restoring the preferred column if the line
+    /// is long enough.
+    fn move_to_line(&mut self, line_index: usize) {
+        let desired_column = std::cmp::max(self.cursor.0, self.preferred_column);
+        let new_column = std::cmp::min(desired_column, self.get_line_length(line_index));
+
+        self.cursor = (new_column, line_index);
+    }
+
+    /// Attempt to move the editor's cursor left one character.
+    fn move_left(&mut self) {
+        let at_start_of_line = self.cursor.0 == 0;
+        let on_first_line = self.cursor.1 == 0;
+
+        if !at_start_of_line {
+            self.cursor.0 -= 1;
+        } else if !on_first_line {
+            let previous_line = self.cursor.1 - 1;
+            self.cursor = (self.get_line_length(previous_line), previous_line);
+        }
+
+        self.update_preferred_column();
+    }
+
+    /// Attempt to move the editor's cursor right one character.
+    fn move_right(&mut self) {
+        let at_end_of_line = self.cursor.0 == self.get_line_length(self.cursor.1);
+        let on_last_line = self.cursor.1 + 1 == self.buffer.line_count();
+
+        if !at_end_of_line {
+            self.cursor.0 += 1;
+        } else if !on_last_line {
+            self.cursor = (0, self.cursor.1 + 1);
+        }
+
+        self.update_preferred_column();
+    }
+
+    /// Move the editor's cursor to the line's first non-whitespace character or, if it's already
+    /// there, to the start of the line.
+    fn move_home(&mut self) {
+        let line = self.buffer.line(self.cursor.1);
+        let indent = line
+            .graphemes(true)
+            .take_while(|grapheme| grapheme.trim().is_empty())
+            .count();
+
+        self.cursor.0 = if self.cursor.0 == indent { 0 } else { indent };
+        self.update_preferred_column();
+    }
+
+    /// Move the editor's cursor to the end of the line.
+    fn move_end(&mut self) {
+        self.cursor.0 = self.get_line_length(self.cursor.1);
+        self.update_preferred_column();
+    }
+
+    /// Move the editor's cursor to the start of the first line.
+    fn move_document_start(&mut self) {
+        self.cursor = (0, 0);
+        self.update_preferred_column();
+    }
+
+    /// Move the editor's cursor to the end of the last line.
+    fn move_document_end(&mut self) {
+        let last_line = self.buffer.line_count() - 1;
+        self.cursor = (self.get_line_length(last_line), last_line);
+        self.update_preferred_column();
+    }
+
+    /// Attempt to move the editor's cursor to the start of the previous word.
+    fn move_word_left(&mut self) {
+        self.cursor = self.get_word_left();
+        self.update_preferred_column();
+    }
+
+    /// Attempt to move the editor's cursor to the end of the next word.
+    fn move_word_right(&mut self) {
+        self.cursor = self.get_word_right();
+        self.update_preferred_column();
+    }
+
+    /// Get the position of the start of the word preceding the cursor, skipping whitespace. At the
+    /// start of a line, this is the end of the previous line.
+    fn get_word_left(&self) -> (usize, usize) {
+        let (column, line_index) = self.cursor;
+
+        let at_start_of_line = column == 0;
+        if at_start_of_line {
+            let on_first_line = line_index == 0;
+            if on_first_line {
+                return self.cursor;
+            }
+
+            let previous_line = line_index - 1;
+            return (self.get_line_length(previous_line), previous_line);
+        }
+
+        let line = self.buffer.line(line_index);
+        let prefix = &line[..get_byte_index(&line, column)];
+
+        let start = prefix
+            .split_word_bound_indices()
+            .rev()
+            .find(|(_, segment)| !segment.trim().is_empty())
+            .map_or(0, |(index, _)| index);
+
+        self.get_position((start, line_index))
+    }
+
+    /// Get the position of the end of the word following the cursor, skipping whitespace. At the
+    /// end of a line, this is the start of the next line.
+    fn get_word_right(&self) -> (usize, usize) {
+        let (column, line_index) = self.cursor;
+
+        let at_end_of_line = column == self.get_line_length(line_index);
+        if at_end_of_line {
+            let on_last_line = line_index + 1 == self.buffer.line_count();
+            if on_last_line {
+                return self.cursor;
+            }
+
+            return (0, line_index + 1);
+        }
+
+        let line = self.buffer.line(line_index);
+        let start = get_byte_index(&line, column);
+        let suffix = &line[start..];
+
+        let end = suffix
+            .split_word_bound_indices()
+            .find(|(_, segment)| !segment.trim().is_empty())
+            .map_or(suffix.len(), |(index, segment)| index + segment.len());
+
+        self.get_position((start + end, line_index))
+    }
+
+    /// Record the cursor's current column as the one to restore across vertical movements.
+    fn update_preferred_column(&mut self) {
+        self.preferred_column = match self.soft_wrap {
+            Some(_) => self.get_row_position(self.cursor).0,
+            None => self.cursor.0,
+        };
+    }
+
+    /// Insert the specified text, with lines separated by `\n`, at the specified raw (bytes, lines)
+    /// position. Returns the raw position after the inserted text. The cursor is left untouched.
+    fn insert_raw(&mut self, position: (usize, usize), text: &str) -> (usize, usize) {
+        self.buffer.insert(position, text);
+        get_raw_end(position, text)
+    }
+
+    /// Remove the text between the specified raw (bytes, lines) positions, returning it with lines
+    /// joined by `\n`. The cursor is left untouched.
+    fn remove_raw(&mut self, start: (usize, usize), end: (usize, usize)) -> String {
+        self.buffer.remove(start, end)
+    }
+
+    /// Clamp the specified (columns, lines) position to the editor's current value.
+    fn clamp_position(&self, position: (usize, usize)) -> (usize, usize) {
+        let line = std::cmp::min(position.1, self.buffer.line_count() - 1);
+        let column = std::cmp::min(position.0, self.get_line_length(line));
+
+        (column, line)
+    }
+
+    /// Convert the specified raw (bytes, lines) position to a (columns, lines) position.
+    fn get_position(&self, raw_position: (usize, usize)) -> (usize, usize) {
+        let line = self.buffer.line(raw_position.1);
+        (
+            line[..raw_position.0].graphemes(true).count(),
+            raw_position.1,
+        )
+    }
+
+    /// Convert the specified (columns, lines) position to a raw (bytes, lines) position.
+    fn get_raw_position(&self, position: (usize, usize)) -> (usize, usize) {
+        (
+            get_byte_index(&self.buffer.line(position.1), position.0),
+            position.1,
+        )
+    }
+
+    /// Get the specified line's length in grapheme clusters.
+    fn get_line_length(&self, line_index: usize) -> usize {
+        self.buffer.line(line_index).graphemes(true).count()
+    }
+}
+
+/// Get the specified byte range of a line, borrowing from the editor where the line is borrowed.
+fn get_line_slice(line: Cow<'_, str>, range: std::ops::Range<usize>) -> Cow<'_, str> {
+    match line {
+        Cow::Borrowed(line) => Cow::Borrowed(&line[range]),
+        Cow::Owned(line) => Cow::Owned(line[range].to_string()),
+    }
+}
+
+/// Normalize the specified text's line breaks to `\n` in multi-line mode or remove them in
+/// single-line mode.
+fn normalize_newlines(value: &str, multi_line: bool) -> String {
+    if multi_line {
+        value.replace("\r\n", "\n").replace('\r', "\n")
+    } else {
+        value.replace(['\n', '\r'], "")
+    }
+}
+
+/// Get the raw (bytes, lines) position after the specified text, with lines separated by `\n`, if
+/// it were inserted at the specified raw position.
+fn get_raw_end(position: (usize, usize), text: &str) -> (usize, usize) {
+    match text.rfind('\n') {
+        Some(index) => (
+            text.len() - index - 1,
+            position.1 + text.matches('\n').count(),
+        ),
+        None => (position.0 + text.len(), position.1),
+    }
+}
+
+/// Get the raw (bytes, lines) position which corresponds to the specified one after the text
+/// between the start and old end positions was replaced by text ending at the new end position.
+fn get_adjusted_raw_position(
+    position: (usize, usize),
+    start: (usize, usize),
+    old_end: (usize, usize),
+    new_end: (usize, usize),
+) -> (usize, usize) {
+    let (position_key, start_key, old_end_key) = (
+        (position.1, position.0),
+        (start.1, start.0),
+        (old_end.1, old_end.0),
+    );
+
+    if position_key <= start_key {
+        position
+    } else if position_key <= old_end_key {
+        new_end
+    } else if position.1 == old_end.1 {
+        (new_end.0 + position.0 - old_end.0, new_end.1)
+    } else {
+        (position.0, position.1 + new_end.1 - old_end.1)
+    }
+}
+
+/// Order the specified (columns, lines) positions as (start, end).
+fn order_positions(a: (usize, usize), b: (usize, usize)) -> ((usize, usize), (usize, usize)) {
+    if (a.1, a.0) <= (b.1, b.0) {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+/// Get the specified text's width in terminal display columns, measured per grapheme cluster so
+/// that combining marks and joined emoji sequences are not over-counted.
+fn get_display_width(text: &str) -> usize {
+    text.graphemes(true).map(|grapheme| grapheme.width()).sum()
+}
+
+/// Get the byte index of the specified grapheme column in the line, clamped to the line's end.
+fn get_byte_index(line: &str, column: usize) -> usize {
+    line.grapheme_indices(true)
+        .nth(column)
+        .map_or(line.len(), |(index, _)| index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! svec {
+        ($($x:expr),*) => (vec![$($x.to_string()),*]);
+    }
+
+    macro_rules! assert_text {
+        ($text: ident, $cursor: expr, $value: expr, $lines: expr) => {
+            assert_eq!($cursor, $text.cursor());
+            assert_eq!($value, $text.value());
+            assert_eq!($lines, $text.lines().collect::<Vec<_>>());
+        };
+    }
+
+    #[test]
+    fn new() {
+        let text = Text::new(false);
+        assert_text!(text, (0, 0), "", svec![""]);
+    }
+
+    #[test]
+    fn from() {
+        let text = Text::from("a\nbc", (1, 1), true);
+        assert_text!(text, (1, 1), "a\nbc", svec!["a", "bc"]);
+    }
+
+    #[test]
+    fn from_carriage_returns() {
+        let text = Text::from("a\rb\r\nc", (1, 1), true);
+        assert_text!(text, (1, 1), "a\nb\nc", svec!["a", "b", "c"]);
+
+        let text = Text::from("a\rb", (1, 0), false);
+        assert_text!(text, (1, 0), "ab", svec!["ab"]);
+    }
+
+    #[test]
+    fn from_clamp_cursor() {
+        let text = Text::from("a\nbc", (5, 5), true);
+        assert_text!(text, (2, 1), "a\nbc", svec!["a", "bc"]);
+    }
+
+    #[test]
+    fn from_collapse_single_line() {
+        let text = Text::from("a\n\nbc", (1, 0), false);
+        assert_text!(text, (1, 0), "abc", svec!["abc"]);
+    }
+
+    #[test]
+    fn from_clamp_cursor_single_line() {
+        let text = Text::from("a\n\nbc", (3, 0), false);
+        assert_text!(text, (3, 0), "abc", svec!["abc"]);
+    }
+
+    #[test]
+    fn blank_lines() {
+        let text = Text::from("abc\n\r\n", (0, 1), true);
+        assert_text!(text, (0, 1), "abc\n\n", svec!["abc", "", ""]);
+    }
+
+    #[test]
+    fn set_cursor() {
+        let mut text = Text::from("a\nbc", (0, 0), true);
+        assert_eq!((0, 0), text.cursor());
+
+        text.set_cursor((1, 1));
+        assert_eq!((1, 1), text.cursor());
+    }
+
+    #[test]
+    fn set_cursor_clamping_graphemes() {
+        let mut text = Text::from("e\u{301}\u{1F600}", (0, 0), true);
+
+        text.set_cursor((5, 0));
+        assert_eq!((2, 0), text.cursor());
+    }
+
+    #[test]
+    fn set_cursor_clamping() {
+        let mut text = Text::from("a\nbc", (0, 0), true);
+        assert_eq!((0, 0), text.cursor());
+
+        text.set_cursor((5, 5));
+        assert_eq!((2, 1), text.cursor());
+    }
+
+    #[test]
+    fn display_cursor() {
+        let text = Text::from("a日b", (2, 0), true);
+        assert_eq!((2, 0), text.cursor());
+        assert_eq!((3, 0), text.display_cursor());
+    }
+
+    #[test]
+    fn display_cursor_zero_width() {
+        let text = Text::from("e\u{301}\u{200B}x", (3, 0), true);
+        assert_eq!((2, 0), text.display_cursor());
+    }
+
+    #[test]
+    fn display_cursor_emoji_sequence() {
+        let family = "\u{1F469}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+        let text = Text::from(&format!("{}a", family), (1, 0), true);
+        assert_eq!((2, 0), text.display_cursor());
+    }
+
+    #[test]
+    fn line_display_width() {
+        let text = Text::from("abc\n\u{1F600}e\u{301}\n", (0, 0), true);
+        assert_eq!(3, text.line_display_width(0));
+        assert_eq!(3, text.line_display_width(1));
+        assert_eq!(0, text.line_display_width(2));
+    }
+
+    #[test]
+    fn handle_input() {
+        let mut text = Text::from("abc\ndef", (2, 1), true);
+        assert_text!(text, (2, 1), "abc\ndef", svec!["abc", "def"]);
+
+        text.handle_input(Key::Char('X'));
+        assert_text!(text, (3, 1), "abc\ndeXf", svec!["abc", "deXf"]);
+
+        text.handle_input(Key::Left);
+        assert_text!(text, (2, 1), "abc\ndeXf", svec!["abc", "deXf"]);
+
+        text.handle_input(Key::Backspace);
+        assert_text!(text, (1, 1), "abc\ndXf", svec!["abc", "dXf"]);
+
+        text.handle_input(Key::Right);
+        text.handle_input(Key::Right);
+        assert_text!(text, (3, 1), "abc\ndXf", svec!["abc", "dXf"]);
+
+        text.handle_input(Key::Char('g'));
+        text.handle_input(Key::Char('h'));
+        text.handle_input(Key::Char('i'));
+        assert_text!(text, (6, 1), "abc\ndXfghi", svec!["abc", "dXfghi"]);
+
+        text.handle_input(Key::Up);
+        assert_text!(text, (3, 0), "abc\ndXfghi", svec!["abc", "dXfghi"]);
+
+        text.handle_input(Key::Left);
+        text.handle_input(Key::Left);
+        assert_text!(text, (1, 0), "abc\ndXfghi", svec!["abc", "dXfghi"]);
+
+        text.handle_input(Key::Down);
+        assert_text!(text, (1, 1), "abc\ndXfghi", svec!["abc", "dXfghi"]);
+
+        text.handle_input(Key::Backspace);
+        text.handle_input(Key::Backspace);
+        assert_text!(text, (3, 0), "abcXfghi", svec!["abcXfghi"]);
+
+        text.handle_input(Key::Right);
+        text.handle_input(Key::Right);
+        assert_text!(text, (5, 0), "abcXfghi", svec!["abcXfghi"]);
+
+        text.handle_input(Key::Enter);
+        assert_text!(text, (0, 1), "abcXf\nghi", svec!["abcXf", "ghi"]);
+
+        text.handle_input(Key::Left);
+        assert_text!(text, (5, 0), "abcXf\nghi", svec!["abcXf", "ghi"]);
+
+        text.handle_input(Key::Down);
+        assert_text!(text, (3, 1), "abcXf\nghi", svec!["abcXf", "ghi"]);
+    }
+
+    #[test]
+    fn handle_input_single_line() {
+        let mut text = Text::from("abcdef", (3, 0), false);
+        assert_text!(text, (3, 0), "abcdef", svec!["abcdef"]);
+
+        text.handle_input(Key::Char('X'));
+        assert_text!(text, (4, 0), "abcXdef", svec!["abcXdef"]);
+
+        text.handle_input(Key::Enter);
+        assert_text!(text, (4, 0), "abcXdef", svec!["abcXdef"]);
+
+        text.handle_input(Key::Up);
+        assert_text!(text, (4, 0), "abcXdef", svec!["abcXdef"]);
+
+        text.handle_input(Key::Down);
+        assert_text!(text, (4, 0), "abcXdef", svec!["abcXdef"]);
+
+        text.handle_input(Key::Backspace);
+        assert_text!(text, (3, 0), "abcdef", svec!["abcdef"]);
+
+        text.set_cursor((0, 0));
+        text.move_left();
+        assert_text!(text, (0, 0), "abcdef", svec!["abcdef"]);
+
+        text.set_cursor((6, 0));
+        text.move_right();
+        assert_text!(text, (6, 0), "abcdef", svec!["abcdef"]);
+    }
+
+    #[test]
+    fn select_right() {
+        let mut text = Text::from("abc", (0, 0), true);
+
+        text.handle_input(Key::SelectRight);
+        text.handle_input(Key::SelectRight);
+
+        assert_text!(text, (2, 0), "abc", svec!["abc"]);
+        assert_eq!(Some(Range::new((0, 0), (2, 0))), text.selection());
+        assert_eq!(Some("ab".to_string()), text.selected_text());
+    }
+
+    #[test]
+    fn select_backwards() {
+        let mut text = Text::from("abc\ndef", (1, 1), true);
+
+        text.handle_input(Key::SelectUp);
+        text.handle_input(Key::SelectLeft);
+
+        assert_text!(text, (0, 0), "abc\ndef", svec!["abc", "def"]);
+        assert_eq!(Some(Range::new((0, 0), (1, 1))), text.selection());
+        assert_eq!(Some("abc\nd".to_string()), text.selected_text());
+    }
+
+    #[test]
+    fn select_across_lines() {
+        let mut text = Text::from("abc\ndef\nghi", (2, 0), true);
+
+        text.handle_input(Key::SelectDown);
+        text.handle_input(Key::SelectDown);
+
+        assert_eq!(Some("c\ndef\ngh".to_string()), text.selected_text());
+    }
+
+    #[test]
+    fn select_empty() {
+        let mut text = Text::from("abc", (1, 0), true);
+
+        text.handle_input(Key::SelectRight);
+        text.handle_input(Key::SelectLeft);
+
+        assert_eq!(None, text.selection());
+        assert_eq!(None, text.selected_text());
+    }
+
+    #[test]
+    fn select_graphemes() {
+        let mut text = Text::from("ae\u{301}\u{1F600}b", (1, 0), true);
+
+        text.handle_input(Key::SelectRight);
+        text.handle_input(Key::SelectRight);
+
+        assert_eq!(Some("e\u{301}\u{1F600}".to_string()), text.selected_text());
+    }
+
+    #[test]
+    fn move_clears_selection() {
+        let mut text = Text::from("abc\ndef", (1, 0), true);
+
+        text.handle_input(Key::SelectRight);
+        text.handle_input(Key::Down);
+
+        assert_text!(text, (2, 1), "abc\ndef", svec!["abc", "def"]);
+        assert_eq!(None, text.selection());
+    }
+
+    #[test]
+    fn move_left_collapses_selection() {
+        let mut text = Text::from("abcdef", (1, 0), true);
+
+        text.handle_input(Key::SelectRight);
+        text.handle_input(Key::SelectRight);
+        text.handle_input(Key::Left);
+
+        assert_text!(text, (1, 0), "abcdef", svec!["abcdef"]);
+        assert_eq!(None, text.selection());
+    }
+
+    #[test]
+    fn move_right_collapses_selection() {
+        let mut text = Text::from("abcdef", (3, 0), true);
+
+        text.handle_input(Key::SelectLeft);
+        text.handle_input(Key::SelectLeft);
+        text.handle_input(Key::Right);
+
+        assert_text!(text, (3, 0), "abcdef", svec!["abcdef"]);
+        assert_eq!(None, text.selection());
+    }
+
+    #[test]
+    fn set_selection() {
+        let mut text = Text::from("abc\ndef", (0, 0), true);
+
+        text.set_selection((9, 9), (1, 0));
+
+        assert_text!(text, (1, 0), "abc\ndef", svec!["abc", "def"]);
+        assert_eq!(Some(Range::new((1, 0), (3, 1))), text.selection());
+
+        text.clear_selection();
+        assert_eq!(None, text.selection());
+    }
+
+    #[test]
+    fn set_cursor_clears_selection() {
+        let mut text = Text::from("abc", (0, 0), true);
+
+        text.set_selection((0, 0), (2, 0));
+        text.set_cursor((1, 0));
+
+        assert_eq!(None, text.selection());
+    }
+
+    #[test]
+    fn insert_character_replaces_selection() {
+        let mut text = Text::from("abc\ndef", (1, 0), true);
+
+        text.set_selection((1, 0), (2, 1));
+        text.handle_input(Key::Char('X'));
+
+        assert_text!(text, (2, 0), "aXf", svec!["aXf"]);
+        assert_eq!(None, text.selection());
+    }
+
+    #[test]
+    fn backspace_character_deletes_selection() {
+        let mut text = Text::from("abc\ndef", (0, 0), true);
+
+        text.set_selection((2, 1), (1, 0));
+        text.handle_input(Key::Backspace);
+
+        assert_text!(text, (1, 0), "af", svec!["af"]);
+        assert_eq!(None, text.selection());
+    }
+
+    #[test]
+    fn insert_newline_replaces_selection() {
+        let mut text = Text::from("abcdef", (0, 0), true);
+
+        text.set_selection((2, 0), (4, 0));
+        text.handle_input(Key::Enter);
+
+        assert_text!(text, (0, 1), "ab\nef", svec!["ab", "ef"]);
+    }
+
+    #[test]
+    fn undo_typing_coalesces() {
+        let mut text = Text::from("abc", (3, 0), true);
+
+        text.handle_input(Key::Char('d'));
+        text.handle_input(Key::Char('e'));
+        text.handle_input(Key::Char('f'));
+        assert_text!(text, (6, 0), "abcdef", svec!["abcdef"]);
+
+        assert!(text.undo());
+        assert_text!(text, (3, 0), "abc", svec!["abc"]);
+        assert!(!text.undo());
+    }
+
+    #[test]
+    fn undo_typing_interrupted_by_movement() {
+        let mut text = Text::from("abc", (3, 0), true);
+
+        text.handle_input(Key::Char('d'));
+        text.handle_input(Key::Left);
+        text.handle_input(Key::Right);
+        text.handle_input(Key::Char('e'));
+
+        text.handle_input(Key::Undo);
+        assert_text!(text, (4, 0), "abcd", svec!["abcd"]);
+
+        text.handle_input(Key::Undo);
+        assert_text!(text, (3, 0), "abc", svec!["abc"]);
+    }
+
+    #[test]
+    fn undo_backspace() {
+        let mut text = Text::from("abc\ndef", (0, 1), true);
+
+        text.handle_input(Key::Backspace);
+        text.handle_input(Key::Backspace);
+        assert_text!(text, (2, 0), "abdef", svec!["abdef"]);
+
+        text.handle_input(Key::Undo);
+        assert_text!(text, (3, 0), "abcdef", svec!["abcdef"]);
+
+        text.handle_input(Key::Undo);
+        assert_text!(text, (0, 1), "abc\ndef", svec!["abc", "def"]);
+    }
+
+    #[test]
+    fn undo_newline_with_bullet() {
+        let mut text = Text::from(" - abc", (6, 0), true);
+
+        text.handle_input(Key::Enter);
+        assert_text!(text, (3, 1), " - abc\n - ", svec![" - abc", " - "]);
+
+        text.handle_input(Key::Undo);
+        assert_text!(text, (6, 0), " - abc", svec![" - abc"]);
+
+        text.handle_input(Key::Redo);
+        assert_text!(text, (3, 1), " - abc\n - ", svec![" - abc", " - "]);
+    }
+
+    #[test]
+    fn undo_restores_selection() {
+        let mut text = Text::from("abc\ndef", (0, 0), true);
+
+        text.set_selection((1, 0), (2, 1));
+        text.handle_input(Key::Char('X'));
+        text.handle_input(Key::Char('Y'));
+        assert_text!(text, (3, 0), "aXYf", svec!["aXYf"]);
+
+        text.handle_input(Key::Undo);
+        assert_text!(text, (2, 1), "abc\ndef", svec!["abc", "def"]);
+        assert_eq!(Some(Range::new((1, 0), (2, 1))), text.selection());
+
+        text.handle_input(Key::Redo);
+        assert_text!(text, (3, 0), "aXYf", svec!["aXYf"]);
+        assert_eq!(None, text.selection());
+    }
+
+    #[test]
+    fn redo_cleared_by_edit() {
+        let mut text = Text::from("abc", (3, 0), true);
+
+        text.handle_input(Key::Char('d'));
+        text.handle_input(Key::Undo);
+        text.handle_input(Key::Char('e'));
+
+        assert!(!text.redo());
+        assert_text!(text, (4, 0), "abce", svec!["abce"]);
+    }
+
+    #[test]
+    fn redo_kept_after_no_op() {
+        let mut text = Text::from("abc", (3, 0), true);
+
+        text.handle_input(Key::Char('d'));
+        text.handle_input(Key::Undo);
+        text.handle_input(Key::Paste(String::new()));
+
+        assert!(text.redo());
+        assert_text!(text, (4, 0), "abcd", svec!["abcd"]);
+
+        assert!(text.undo());
+        assert!(!text.undo());
+    }
+
+    #[test]
+    fn undo_redo_graphemes() {
+        let mut text = Text::from("a", (1, 0), true);
+
+        text.handle_input(Key::Char('e'));
+        text.handle_input(Key::Char('\u{301}'));
+        text.handle_input(Key::Backspace);
+        assert_text!(text, (1, 0), "a", svec!["a"]);
+
+        text.handle_input(Key::Undo);
+        assert_text!(text, (2, 0), "ae\u{301}", svec!["ae\u{301}"]);
+
+        text.handle_input(Key::Undo);
+        assert_text!(text, (1, 0), "a", svec!["a"]);
+
+        text.handle_input(Key::Redo);
+        text.handle_input(Key::Redo);
+        assert_text!(text, (1, 0), "a", svec!["a"]);
+    }
+
+    #[test]
+    fn move_home() {
+        let mut text = Text::from("  abc", (4, 0), true);
+
+        text.handle_input(Key::Home);
+        assert_eq!((2, 0), text.cursor());
+
+        text.handle_input(Key::Home);
+        assert_eq!((0, 0), text.cursor());
+
+        text.handle_input(Key::Home);
+        assert_eq!((2, 0), text.cursor());
+    }
+
+    #[test]
+    fn move_home_bullet() {
+        let mut text = Text::from(" - abc", (6, 0), true);
+
+        text.handle_input(Key::Home);
+        assert_eq!((1, 0), text.cursor());
+
+        text.handle_input(Key::Home);
+        assert_eq!((0, 0), text.cursor());
+    }
+
+    #[test]
+    fn move_home_unindented() {
+        let mut text = Text::from("abc", (2, 0), true);
+
+        text.handle_input(Key::Home);
+        assert_eq!((0, 0), text.cursor());
+
+        text.handle_input(Key::Home);
+        assert_eq!((0, 0), text.cursor());
+    }
+
+    #[test]
+    fn move_home_blank_line() {
+        let mut text = Text::from("   ", (1, 0), true);
+
+        text.handle_input(Key::Home);
+        assert_eq!((3, 0), text.cursor());
+
+        text.handle_input(Key::Home);
+        assert_eq!((0, 0), text.cursor());
+    }
+
+    #[test]
+    fn move_end() {
+        let mut text = Text::from("abc\ndefgh", (1, 0), true);
+
+        text.handle_input(Key::End);
+        assert_eq!((3, 0), text.cursor());
+
+        text.handle_input(Key::Down);
+        assert_eq!((3, 1), text.cursor());
+    }
+
+    #[test]
+    fn move_home_preferred_column() {
+        let mut text = Text::from("abcdef\nabcdef", (5, 0), true);
+
+        text.handle_input(Key::Home);
+        text.handle_input(Key::Down);
+
+        assert_eq!((0, 1), text.cursor());
+    }
+
+    #[test]
+    fn move_document_start() {
+        let mut text = Text::from("abc\ndef", (2, 1), true);
+
+        text.handle_input(Key::SelectLeft);
+        text.handle_input(Key::DocumentStart);
+
+        assert_eq!((0, 0), text.cursor());
+        assert_eq!(None, text.selection());
+    }
+
+    #[test]
+    fn move_document_end() {
+        let mut text = Text::from("abc\ndef\ngh", (2, 0), true);
+
+        text.handle_input(Key::DocumentEnd);
+        assert_eq!((2, 2), text.cursor());
+
+        text.handle_input(Key::Up);
+        assert_eq!((2, 1), text.cursor());
+    }
+
+    #[test]
+    fn move_word_left() {
+        let mut text = Text::from("foo bar, baz  ", (14, 0), true);
+
+        text.handle_input(Key::WordLeft);
+        assert_eq!((9, 0), text.cursor());
+
+        text.handle_input(Key::WordLeft);
+        assert_eq!((7, 0), text.cursor());
+
+        text.handle_input(Key::WordLeft);
+        assert_eq!((4, 0), text.cursor());
+
+        text.handle_input(Key::WordLeft);
+        assert_eq!((0, 0), text.cursor());
+
+        text.handle_input(Key::WordLeft);
+        assert_eq!((0, 0), text.cursor());
+    }
+
+    #[test]
+    fn move_word_left_mid_word() {
+        let mut text = Text::from("foo barbaz", (7, 0), true);
+
+        text.handle_input(Key::WordLeft);
+
+        assert_eq!((4, 0), text.cursor());
+    }
+
+    #[test]
+    fn move_word_left_wrap_up() {
+        let mut text = Text::from("foo bar\n  baz", (2, 1), true);
+
+        text.handle_input(Key::WordLeft);
+        assert_eq!((0, 1), text.cursor());
+
+        text.handle_input(Key::WordLeft);
+        assert_eq!((7, 0), text.cursor());
+
+        text.handle_input(Key::WordLeft);
+        assert_eq!((4, 0), text.cursor());
+    }
+
+    #[test]
+    fn move_word_right() {
+        let mut text = Text::from("  foo bar.baz", (0, 0), true);
+
+        text.handle_input(Key::WordRight);
+        assert_eq!((5, 0), text.cursor());
+
+        text.handle_input(Key::WordRight);
+        assert_eq!((13, 0), text.cursor());
+
+        text.handle_input(Key::WordRight);
+        assert_eq!((13, 0), text.cursor());
+    }
+
+    #[test]
+    fn move_word_right_wrap_down() {
+        let mut text = Text::from("foo  \nbar", (3, 0), true);
+
+        text.handle_input(Key::WordRight);
+        assert_eq!((5, 0), text.cursor());
+
+        text.handle_input(Key::WordRight);
+        assert_eq!((0, 1), text.cursor());
+
+        text.handle_input(Key::WordRight);
+        assert_eq!((3, 1), text.cursor());
+    }
+
+    #[test]
+    fn move_word_unicode() {
+        let mut text = Text::from("héllo wörld", (0, 0), true);
+
+        text.handle_input(Key::WordRight);
+        assert_eq!((5, 0), text.cursor());
+
+        text.handle_input(Key::WordRight);
+        assert_eq!((11, 0), text.cursor());
+
+        text.handle_input(Key::WordLeft);
+        assert_eq!((6, 0), text.cursor());
+    }
+
+    #[test]
+    fn move_word_vertical_preferred_column() {
+        let mut text = Text::from("abc def\nab\nabcdefgh", (0, 0), true);
+
+        text.handle_input(Key::WordRight);
+        text.handle_input(Key::WordRight);
+        text.handle_input(Key::Down);
+        text.handle_input(Key::Down);
+
+        assert_eq!((7, 2), text.cursor());
+    }
+
+    #[test]
+    fn delete_word_backward() {
+        let mut text = Text::from("foo bar baz", (9, 0), true);
+
+        text.handle_input(Key::DeleteWordBackward);
+        assert_text!(text, (8, 0), "foo bar az", svec!["foo bar az"]);
+
+        text.handle_input(Key::DeleteWordBackward);
+        assert_text!(text, (4, 0), "foo az", svec!["foo az"]);
+    }
+
+    #[test]
+    fn delete_word_backward_joins_lines() {
+        let mut text = Text::from("foo\nbar", (0, 1), true);
+
+        text.handle_input(Key::DeleteWordBackward);
+
+        assert_text!(text, (3, 0), "foobar", svec!["foobar"]);
+    }
+
+    #[test]
+    fn delete_word_backward_selection() {
+        let mut text = Text::from("foo bar", (0, 0), true);
+
+        text.set_selection((1, 0), (2, 0));
+        text.handle_input(Key::DeleteWordBackward);
+
+        assert_text!(text, (1, 0), "fo bar", svec!["fo bar"]);
+    }
+
+    #[test]
+    fn delete_word_forward() {
+        let mut text = Text::from("foo bar baz", (3, 0), true);
+
+        text.handle_input(Key::DeleteWordForward);
+        assert_text!(text, (3, 0), "foo baz", svec!["foo baz"]);
+
+        text.handle_input(Key::Undo);
+        assert_text!(text, (3, 0), "foo bar baz", svec!["foo bar baz"]);
+    }
+
+    #[test]
+    fn delete_word_forward_joins_lines() {
+        let mut text = Text::from("foo\nbar", (3, 0), true);
+
+        text.handle_input(Key::DeleteWordForward);
+
+        assert_text!(text, (3, 0), "foobar", svec!["foobar"]);
+    }
+
+    #[test]
+    fn delete_word_at_document_ends() {
+        let mut text = Text::from("foo", (3, 0), true);
+
+        text.handle_input(Key::Backspace);
+        text.handle_input(Key::Undo);
+
+        text.handle_input(Key::DeleteWordForward);
+        assert_text!(text, (3, 0), "foo", svec!["foo"]);
+
+        text.set_cursor((0, 0));
+        text.handle_input(Key::DeleteWordBackward);
+        assert_text!(text, (0, 0), "foo", svec!["foo"]);
+
+        assert!(!text.undo());
+        assert!(text.redo());
+        assert_text!(text, (2, 0), "fo", svec!["fo"]);
+    }
+
+    #[test]
+    fn paste_key() {
+        let mut text = Text::from(" - abc", (6, 0), true);
+
+        text.handle_input(Key::Paste("\n - def\r\n\n* ghi".to_string()));
+
+        assert_text!(
+            text,
+            (5, 3),
+            " - abc\n - def\n\n* ghi",
+            svec![" - abc", " - def", "", "* ghi"]
+        );
+
+        text.handle_input(Key::Undo);
+        assert_text!(text, (6, 0), " - abc", svec![" - abc"]);
+    }
+
+    #[test]
+    fn paste_key_single_line() {
+        let mut text = Text::from("ab", (1, 0), false);
+
+        text.handle_input(Key::Paste("1\n2".to_string()));
+
+        assert_text!(text, (3, 0), "a12b", svec!["a12b"]);
+    }
+
+    #[test]
+    fn insert_character_end_line() {
+        let mut text = Text::new(true);
+
+        text.insert_character('a');
+        text.insert_character('b');
+        text.insert_character('c');
+
+        assert_text!(text, (3, 0), "abc", svec!["abc"]);
+    }
+
+    #[test]
+    fn insert_character_mid_line() {
+        let mut text = Text::from("abc", (1, 0), true);
+
+        text.insert_character('X');
+
+        assert_text!(text, (2, 0), "aXbc", svec!["aXbc"]);
+    }
+
+    #[test]
+    fn insert_character_start_line() {
+        let mut text = Text::from("abc", (0, 0), true);
+
+        text.insert_character('X');
+
+        assert_text!(text, (1, 0), "Xabc", svec!["Xabc"]);
+    }
+
+    #[test]
+    fn insert_character_multi_byte() {
+        let mut text = Text::from("ab", (1, 0), true);
+
+        text.insert_character('é');
+        text.insert_character('ü');
+
+        assert_text!(text, (3, 0), "aéüb", svec!["aéüb"]);
+    }
+
+    #[test]
+    fn insert_character_combining_mark() {
+        let mut text = Text::from("ab", (1, 0), true);
+
+        text.insert_character('e');
+        text.insert_character('\u{301}');
+
+        assert_text!(text, (2, 0), "ae\u{301}b", svec!["ae\u{301}b"]);
+    }
+
+    #[test]
+    fn insert_character_line_break_multi_line() {
+        let mut text = Text::from(" - ab", (4, 0), true);
+
+        text.handle_input(Key::Char('\n'));
+        assert_text!(text, (3, 1), " - a\n - b", svec![" - a", " - b"]);
+
+        text.handle_input(Key::Char('\r'));
+        assert_text!(
+            text,
+            (3, 2),
+            " - a\n - \n - b",
+            svec![" - a", " - ", " - b"]
+        );
+    }
+
+    #[test]
+    fn insert_character_line_break_single_line() {
+        let mut text = Text::from("ab", (1, 0), false);
+
+        text.handle_input(Key::Char('\n'));
+        text.handle_input(Key::Char('\r'));
+
+        assert_text!(text, (1, 0), "ab", svec!["ab"]);
+    }
+
+    #[test]
+    fn backspace_character_all() {
+        let mut text = Text::from("abc", (3, 0), true);
+
+        text.backspace_character();
+        text.backspace_character();
+        text.backspace_character();
+
+        assert_text!(text, (0, 0), "", svec![""]);
+    }
+
+    #[test]
+    fn backspace_character_mid_line() {
+        let mut text = Text::from("abc", (2, 0), true);
+
+        text.backspace_character();
+        text.backspace_character();
+        text.backspace_character();
+
+        assert_text!(text, (0, 0), "c", svec!["c"]);
+    }
+
+    #[test]
+    fn backspace_character_start_line() {
+        let mut text = Text::from("abc", (0, 0), true);
+
+        text.backspace_character();
+        text.backspace_character();
+        text.backspace_character();
+
+        assert_text!(text, (0, 0), "abc", svec!["abc"]);
+    }
+
+    #[test]
+    fn backspace_character_multi_line() {
+        let mut text = Text::from("abc\ndef", (0, 1), true);
+
+        text.backspace_character();
+
+        assert_text!(text, (3, 0), "abcdef", svec!["abcdef"]);
+    }
+
+    #[test]
+    fn backspace_character_grapheme_cluster() {
+        let family = "\u{1F469}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+        let mut text = Text::from(&format!("a{}b", family), (2, 0), true);
+
+        text.backspace_character();
+
+        assert_text!(text, (1, 0), "ab", svec!["ab"]);
+    }
+
+    #[test]
+    fn delete_character_all() {
+        let mut text = Text::from("abc", (0, 0), true);
+
+        text.delete_character();
+        text.delete_character();
+        text.delete_character();
+
+        assert_text!(text, (0, 0), "", svec![""]);
     }
 
-    /// Attempt to move the editor's cursor left one character.
-    fn move_left(&mut self) {
-        let at_start_of_line = self.cursor.0 == 0;
-        let on_first_line = self.cursor.1 == 0;
+    #[test]
+    fn delete_character_mid_line() {
+        let mut text = Text::from("abc", (1, 0), true);
 
-        if !at_start_of_line {
-            self.cursor.0 -= 1;
-        } else if !on_first_line {
-            let previous_line = self.cursor.1 - 1;
-            self.cursor = (self.get_line_length(previous_line), previous_line);
-        }
+        text.delete_character();
+        text.delete_character();
+        text.delete_character();
 
-        self.preferred_column = self.cursor.0;
+        assert_text!(text, (1, 0), "a", svec!["a"]);
     }
 
-    /// Attempt to move the editor's cursor right one character.
-    fn move_right(&mut self) {
-        let at_end_of_line = self.cursor.0 == self.get_line_length(self.cursor.1);
-        let on_last_line = self.cursor.1 + 1 == self.lines.len();
+    #[test]
+    fn delete_character_end_line() {
+        let mut text = Text::from("abc", (3, 0), true);
 
-        if !at_end_of_line {
-            self.cursor.0 += 1;
-        } else if !on_last_line {
-            self.cursor = (0, self.cursor.1 + 1);
-        }
+        text.delete_character();
 
-        self.preferred_column = self.cursor.0;
+        assert_text!(text, (3, 0), "abc", svec!["abc"]);
     }
 
-    /// Get the specified line's length.
-    fn get_line_length(&self, line_index: usize) -> usize {
-        self.lines[line_index].len()
-    }
-}
+    #[test]
+    fn delete_character_multi_line() {
+        let mut text = Text::from("abc\ndef", (3, 0), true);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        text.delete_character();
 
-    macro_rules! svec {
-        ($($x:expr),*) => (vec![$($x.to_string()),*]);
+        assert_text!(text, (3, 0), "abcdef", svec!["abcdef"]);
     }
 
-    macro_rules! assert_text {
-        ($text: ident, $cursor: expr, $value: expr, $lines: expr) => {
-            assert_eq!($cursor, $text.cursor());
-            assert_eq!($value, $text.value());
-            assert_eq!(&$lines, $text.lines());
-        };
+    #[test]
+    fn delete_character_grapheme_cluster() {
+        let family = "\u{1F469}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+        let mut text = Text::from(&format!("a{}b", family), (1, 0), true);
+
+        text.delete_character();
+
+        assert_text!(text, (1, 0), "ab", svec!["ab"]);
     }
 
     #[test]
-    fn new() {
-        let text = Text::new(false);
-        assert_text!(text, (0, 0), "", svec![""]);
+    fn delete_character_selection() {
+        let mut text = Text::from("abc\ndef", (0, 0), true);
+
+        text.set_selection((1, 0), (1, 1));
+        text.handle_input(Key::Delete);
+
+        assert_text!(text, (1, 0), "aef", svec!["aef"]);
     }
 
     #[test]
-    fn from() {
-        let text = Text::from("a\nbc", (1, 1), true);
-        assert_text!(text, (1, 1), "a\nbc", svec!["a", "bc"]);
+    fn insert_str() {
+        let mut text = Text::from("abc", (1, 0), true);
+
+        text.insert_str("XY");
+
+        assert_text!(text, (3, 0), "aXYbc", svec!["aXYbc"]);
     }
 
     #[test]
-    fn from_clamp_cursor() {
-        let text = Text::from("a\nbc", (5, 5), true);
-        assert_text!(text, (2, 1), "a\nbc", svec!["a", "bc"]);
+    fn insert_str_multi_line() {
+        let mut text = Text::from("abc", (1, 0), true);
+
+        text.insert_str("1\n2\r\n3\r4\n");
+
+        assert_text!(
+            text,
+            (0, 4),
+            "a1\n2\n3\n4\nbc",
+            svec!["a1", "2", "3", "4", "bc"]
+        );
     }
 
     #[test]
-    fn from_collapse_single_line() {
-        let text = Text::from("a\n\nbc", (1, 0), false);
-        assert_text!(text, (1, 0), "abc", svec!["abc"]);
+    fn insert_str_single_line() {
+        let mut text = Text::from("abc", (1, 0), false);
+
+        text.insert_str("1\n2\r\n3\r");
+
+        assert_text!(text, (4, 0), "a123bc", svec!["a123bc"]);
     }
 
     #[test]
-    fn from_clamp_cursor_single_line() {
-        let text = Text::from("a\n\nbc", (3, 0), false);
-        assert_text!(text, (3, 0), "abc", svec!["abc"]);
+    fn insert_str_no_bullet_continuation() {
+        let mut text = Text::from(" - abc", (6, 0), true);
+
+        text.insert_str("\ndef");
+
+        assert_text!(text, (3, 1), " - abc\ndef", svec![" - abc", "def"]);
     }
 
     #[test]
-    fn blank_lines() {
-        let text = Text::from("abc\n\r\n", (0, 1), true);
-        assert_text!(text, (0, 1), "abc\n\n", svec!["abc", "", ""]);
+    fn insert_str_replaces_selection() {
+        let mut text = Text::from("abc\ndef", (0, 0), true);
+
+        text.set_selection((1, 0), (2, 1));
+        text.insert_str("X\nY");
+
+        assert_text!(text, (1, 1), "aX\nYf", svec!["aX", "Yf"]);
     }
 
     #[test]
-    fn set_cursor() {
-        let mut text = Text::from("a\nbc", (0, 0), true);
-        assert_eq!((0, 0), text.cursor());
+    fn insert_str_graphemes() {
+        let mut text = Text::from("a", (1, 0), true);
 
-        text.set_cursor((1, 1));
-        assert_eq!((1, 1), text.cursor());
+        text.insert_str("e\u{301}\u{1F600}");
+
+        assert_text!(
+            text,
+            (3, 0),
+            "ae\u{301}\u{1F600}",
+            svec!["ae\u{301}\u{1F600}"]
+        );
     }
 
     #[test]
-    fn set_cursor_clamping() {
-        let mut text = Text::from("a\nbc", (0, 0), true);
-        assert_eq!((0, 0), text.cursor());
+    fn insert_str_undo() {
+        let mut text = Text::from("abc", (1, 0), true);
 
-        text.set_cursor((5, 5));
-        assert_eq!((2, 1), text.cursor());
+        text.insert_str("1\n2");
+        text.insert_str("3");
+        assert_text!(text, (2, 1), "a1\n23bc", svec!["a1", "23bc"]);
+
+        text.undo();
+        assert_text!(text, (1, 1), "a1\n2bc", svec!["a1", "2bc"]);
+
+        text.undo();
+        assert_text!(text, (1, 0), "abc", svec!["abc"]);
     }
 
     #[test]
-    fn handle_input() {
-        let mut text = Text::from("abc\ndef", (2, 1), true);
-        assert_text!(text, (2, 1), "abc\ndef", svec!["abc", "def"]);
+    fn insert_str_empty_keeps_redo() {
+        let mut text = Text::from("abc", (1, 0), true);
 
-        text.handle_input(Key::Char('X'));
-        assert_text!(text, (3, 1), "abc\ndeXf", svec!["abc", "deXf"]);
+        text.insert_str("1");
+        text.undo();
 
-        text.handle_input(Key::Left);
-        assert_text!(text, (2, 1), "abc\ndeXf", svec!["abc", "deXf"]);
+        text.insert_str("");
+        assert_text!(text, (1, 0), "abc", svec!["abc"]);
 
-        text.handle_input(Key::Backspace);
-        assert_text!(text, (1, 1), "abc\ndXf", svec!["abc", "dXf"]);
+        assert!(!text.undo());
+        assert!(text.redo());
+        assert_text!(text, (2, 0), "a1bc", svec!["a1bc"]);
+    }
 
-        text.handle_input(Key::Right);
-        text.handle_input(Key::Right);
-        assert_text!(text, (3, 1), "abc\ndXf", svec!["abc", "dXf"]);
+    #[test]
+    fn delete_range() {
+        let mut text = Text::from("abc\ndef\nghi", (0, 0), true);
 
-        text.handle_input(Key::Char('g'));
-        text.handle_input(Key::Char('h'));
-        text.handle_input(Key::Char('i'));
-        assert_text!(text, (6, 1), "abc\ndXfghi", svec!["abc", "dXfghi"]);
+        text.delete_range((2, 1), (1, 0));
 
-        text.handle_input(Key::Up);
-        assert_text!(text, (3, 0), "abc\ndXfghi", svec!["abc", "dXfghi"]);
+        assert_text!(text, (0, 0), "af\nghi", svec!["af", "ghi"]);
+    }
 
-        text.handle_input(Key::Left);
-        text.handle_input(Key::Left);
-        assert_text!(text, (1, 0), "abc\ndXfghi", svec!["abc", "dXfghi"]);
+    #[test]
+    fn delete_range_cursor_inside() {
+        let mut text = Text::from("abcdef", (3, 0), true);
 
-        text.handle_input(Key::Down);
-        assert_text!(text, (1, 1), "abc\ndXfghi", svec!["abc", "dXfghi"]);
+        text.delete_range((1, 0), (5, 0));
 
-        text.handle_input(Key::Backspace);
-        text.handle_input(Key::Backspace);
-        assert_text!(text, (3, 0), "abcXfghi", svec!["abcXfghi"]);
+        assert_text!(text, (1, 0), "af", svec!["af"]);
+    }
 
-        text.handle_input(Key::Right);
-        text.handle_input(Key::Right);
-        assert_text!(text, (5, 0), "abcXfghi", svec!["abcXfghi"]);
+    #[test]
+    fn delete_range_clamped() {
+        let mut text = Text::from("abc\ndef", (1, 0), true);
 
-        text.handle_input(Key::Enter);
-        assert_text!(text, (0, 1), "abcXf\nghi", svec!["abcXf", "ghi"]);
+        text.delete_range((2, 0), (9, 9));
 
-        text.handle_input(Key::Left);
-        assert_text!(text, (5, 0), "abcXf\nghi", svec!["abcXf", "ghi"]);
+        assert_text!(text, (1, 0), "ab", svec!["ab"]);
+    }
 
-        text.handle_input(Key::Down);
-        assert_text!(text, (3, 1), "abcXf\nghi", svec!["abcXf", "ghi"]);
+    #[test]
+    fn replace_range_cursor_before() {
+        let mut text = Text::from("abc def", (1, 0), true);
+
+        text.replace_range((4, 0), (7, 0), "X\nY");
+
+        assert_text!(text, (1, 0), "abc X\nY", svec!["abc X", "Y"]);
     }
 
     #[test]
-    fn handle_input_single_line() {
-        let mut text = Text::from("abcdef", (3, 0), false);
-        assert_text!(text, (3, 0), "abcdef", svec!["abcdef"]);
+    fn replace_range_cursor_after_same_line() {
+        let mut text = Text::from("abc def ghi", (9, 0), true);
 
-        text.handle_input(Key::Char('X'));
-        assert_text!(text, (4, 0), "abcXdef", svec!["abcXdef"]);
+        text.replace_range((4, 0), (7, 0), "X\nYZ");
 
-        text.handle_input(Key::Enter);
-        assert_text!(text, (4, 0), "abcXdef", svec!["abcXdef"]);
+        assert_text!(text, (4, 1), "abc X\nYZ ghi", svec!["abc X", "YZ ghi"]);
+    }
 
-        text.handle_input(Key::Up);
-        assert_text!(text, (4, 0), "abcXdef", svec!["abcXdef"]);
+    #[test]
+    fn replace_range_cursor_after_later_line() {
+        let mut text = Text::from("abc\ndef\nghi", (2, 2), true);
 
-        text.handle_input(Key::Down);
-        assert_text!(text, (4, 0), "abcXdef", svec!["abcXdef"]);
+        text.replace_range((1, 0), (1, 1), "X");
 
-        text.handle_input(Key::Backspace);
-        assert_text!(text, (3, 0), "abcdef", svec!["abcdef"]);
+        assert_text!(text, (2, 1), "aXef\nghi", svec!["aXef", "ghi"]);
+    }
 
-        text.set_cursor((0, 0));
-        text.move_left();
-        assert_text!(text, (0, 0), "abcdef", svec!["abcdef"]);
+    #[test]
+    fn replace_range_cursor_at_start() {
+        let mut text = Text::from("abc", (1, 0), true);
 
-        text.set_cursor((6, 0));
-        text.move_right();
-        assert_text!(text, (6, 0), "abcdef", svec!["abcdef"]);
+        text.replace_range((1, 0), (2, 0), "XYZ");
+
+        assert_text!(text, (1, 0), "aXYZc", svec!["aXYZc"]);
     }
 
     #[test]
-    fn insert_character_end_line() {
-        let mut text = Text::new(true);
+    fn replace_range_adjusts_selection() {
+        let mut text = Text::from("abc def ghi", (0, 0), true);
 
-        text.insert_character('a');
-        text.insert_character('b');
-        text.insert_character('c');
+        text.set_selection((8, 0), (11, 0));
+        text.replace_range((0, 0), (3, 0), "X");
 
-        assert_text!(text, (3, 0), "abc", svec!["abc"]);
+        assert_eq!(Some(Range::new((6, 0), (9, 0))), text.selection());
+        assert_eq!(Some("ghi".to_string()), text.selected_text());
     }
 
     #[test]
-    fn insert_character_mid_line() {
-        let mut text = Text::from("abc", (1, 0), true);
+    fn replace_range_graphemes() {
+        let mut text = Text::from("e\u{301}\u{1F600}x", (3, 0), true);
 
-        text.insert_character('X');
+        text.replace_range((0, 0), (2, 0), "ab");
 
-        assert_text!(text, (2, 0), "aXbc", svec!["aXbc"]);
+        assert_text!(text, (3, 0), "abx", svec!["abx"]);
     }
 
     #[test]
-    fn insert_character_start_line() {
-        let mut text = Text::from("abc", (0, 0), true);
+    fn replace_range_single_line() {
+        let mut text = Text::from("abc", (3, 0), false);
 
-        text.insert_character('X');
+        text.replace_range((1, 0), (2, 0), "X\nY");
 
-        assert_text!(text, (1, 0), "Xabc", svec!["Xabc"]);
+        assert_text!(text, (4, 0), "aXYc", svec!["aXYc"]);
     }
 
     #[test]
-    fn backspace_character_all() {
-        let mut text = Text::from("abc", (3, 0), true);
+    fn replace_range_undo() {
+        let mut text = Text::from("abc def", (7, 0), true);
 
-        text.backspace_character();
-        text.backspace_character();
-        text.backspace_character();
+        text.replace_range((0, 0), (3, 0), "X");
+        text.undo();
 
-        assert_text!(text, (0, 0), "", svec![""]);
+        assert_text!(text, (7, 0), "abc def", svec!["abc def"]);
     }
 
     #[test]
-    fn backspace_character_mid_line() {
-        let mut text = Text::from("abc", (2, 0), true);
+    fn replace_range_empty_keeps_redo() {
+        let mut text = Text::from("abc def", (7, 0), true);
 
-        text.backspace_character();
-        text.backspace_character();
-        text.backspace_character();
+        text.replace_range((0, 0), (3, 0), "X");
+        text.undo();
 
-        assert_text!(text, (0, 0), "c", svec!["c"]);
+        text.replace_range((2, 0), (2, 0), "");
+        text.delete_range((4, 0), (4, 0));
+        assert_text!(text, (7, 0), "abc def", svec!["abc def"]);
+
+        assert!(!text.undo());
+        assert!(text.redo());
+        assert_text!(text, (5, 0), "X def", svec!["X def"]);
     }
 
     #[test]
-    fn backspace_character_start_line() {
-        let mut text = Text::from("abc", (0, 0), true);
+    fn set_value() {
+        let mut text = Text::from("abc\ndef", (3, 1), true);
 
-        text.backspace_character();
-        text.backspace_character();
-        text.backspace_character();
+        text.set_value("a\r\nbcdef\n");
 
-        assert_text!(text, (0, 0), "abc", svec!["abc"]);
+        assert_text!(text, (3, 1), "a\nbcdef\n", svec!["a", "bcdef", ""]);
     }
 
     #[test]
-    fn backspace_character_multi_line() {
-        let mut text = Text::from("abc\ndef", (0, 1), true);
+    fn set_value_clamps_cursor() {
+        let mut text = Text::from("abc\ndef", (0, 0), true);
 
-        text.backspace_character();
+        text.set_selection((0, 0), (3, 1));
+        text.set_value("x");
 
-        assert_text!(text, (3, 0), "abcdef", svec!["abcdef"]);
+        assert_text!(text, (1, 0), "x", svec!["x"]);
+        assert_eq!(None, text.selection());
+
+        text.undo();
+        assert_text!(text, (3, 1), "abc\ndef", svec!["abc", "def"]);
+        assert_eq!(Some(Range::new((0, 0), (3, 1))), text.selection());
     }
 
     #[test]
@@ -605,6 +2514,15 @@ mod tests {
         assert_text!(text, (3, 0), "abcdef", svec!["abcdef"]);
     }
 
+    #[test]
+    fn insert_newline_multi_byte() {
+        let mut text = Text::from("aéb", (2, 0), true);
+
+        text.insert_newline();
+
+        assert_text!(text, (0, 1), "aé\nb", svec!["aé", "b"]);
+    }
+
     #[test]
     fn move_up_start_line() {
         let mut text = Text::from("abc\ndef", (0, 1), true);
@@ -695,6 +2613,56 @@ mod tests {
         assert_text!(text, (3, 0), "abcdef", svec!["abcdef"]);
     }
 
+    #[test]
+    fn move_page_down() {
+        let mut text = Text::from("abcd\na\nabc\nabcd\nab", (3, 0), true);
+        text.set_page_height(2);
+
+        text.handle_input(Key::PageDown);
+        assert_eq!((3, 2), text.cursor());
+
+        text.handle_input(Key::PageDown);
+        assert_eq!((2, 4), text.cursor());
+
+        text.handle_input(Key::PageDown);
+        assert_eq!((2, 4), text.cursor());
+    }
+
+    #[test]
+    fn move_page_up() {
+        let mut text = Text::from("abcd\na\nabc\nabcd\nabcd", (4, 4), true);
+        text.set_page_height(3);
+
+        text.handle_input(Key::PageUp);
+        assert_eq!((1, 1), text.cursor());
+
+        text.handle_input(Key::PageUp);
+        assert_eq!((4, 0), text.cursor());
+    }
+
+    #[test]
+    fn move_page_default_height() {
+        let mut text = Text::from("a\nb\nc", (0, 0), true);
+        assert_eq!(1, text.page_height());
+
+        text.set_page_height(0);
+        assert_eq!(1, text.page_height());
+
+        text.handle_input(Key::PageDown);
+        assert_eq!((0, 1), text.cursor());
+    }
+
+    #[test]
+    fn move_page_single_line() {
+        let mut text = Text::from("abcdef", (3, 0), false);
+        text.set_page_height(5);
+
+        text.handle_input(Key::PageUp);
+        text.handle_input(Key::PageDown);
+
+        assert_eq!((3, 0), text.cursor());
+    }
+
     #[test]
     fn move_left_mid_line() {
         let mut text = Text::from("abc", (2, 0), true);
@@ -740,6 +2708,15 @@ mod tests {
         assert_text!(text, (0, 1), "abc\n\ndef", svec!["abc", "", "def"]);
     }
 
+    #[test]
+    fn move_left_combining_mark() {
+        let mut text = Text::from("ae\u{301}b", (2, 0), true);
+
+        text.move_left();
+
+        assert_text!(text, (1, 0), "ae\u{301}b", svec!["ae\u{301}b"]);
+    }
+
     #[test]
     fn move_left_single_line() {
         let mut text = Text::from("abcdef", (0, 0), false);
@@ -794,6 +2771,16 @@ mod tests {
         assert_text!(text, (0, 1), "abc\n\ndef", svec!["abc", "", "def"]);
     }
 
+    #[test]
+    fn move_right_emoji() {
+        let mut text = Text::from("a\u{1F600}b", (1, 0), true);
+
+        text.move_right();
+        text.insert_character('X');
+
+        assert_text!(text, (3, 0), "a\u{1F600}Xb", svec!["a\u{1F600}Xb"]);
+    }
+
     #[test]
     fn move_right_single_line() {
         let mut text = Text::from("abcdef", (6, 0), false);
@@ -802,4 +2789,39 @@ mod tests {
 
         assert_text!(text, (6, 0), "abcdef", svec!["abcdef"]);
     }
+
+    #[test]
+    fn rope_buffer_editing() {
+        let mut text = Text::with_buffer(RopeBuffer::from("Hello,\n - world!"), (6, 0), true);
+
+        text.handle_input(Key::Enter);
+        text.handle_input(Key::Char('a'));
+        text.handle_input(Key::Down);
+        text.handle_input(Key::End);
+        text.handle_input(Key::Enter);
+        text.handle_input(Key::Char('日'));
+        assert_text!(
+            text,
+            (4, 3),
+            "Hello,\na\n - world!\n - 日",
+            svec!["Hello,", "a", " - world!", " - 日"]
+        );
+
+        text.set_selection((3, 0), (2, 2));
+        text.handle_input(Key::Backspace);
+        assert_text!(
+            text,
+            (3, 0),
+            "Hel world!\n - 日",
+            svec!["Hel world!", " - 日"]
+        );
+
+        while text.undo() {}
+        assert_text!(
+            text,
+            (6, 0),
+            "Hello,\n - world!",
+            svec!["Hello,", " - world!"]
+        );
+    }
 }
@@ -0,0 +1,275 @@
+//! Continuation of list items onto new lines.
+
+use crate::{Text, TextBuffer};
+
+/// A list item marker, recognized after a line's indentation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ListMarker {
+    /// A literal bullet, e.g. `"- "` or `"* "`, continued unchanged.
+    Bullet(String),
+
+    /// A number followed by the specified delimiter and a space, e.g. `'.'` for `"1. "`,
+    /// continued with the next number.
+    Numbered(char),
+
+    /// A checkbox, `"[ ] "` or `"[x] "`, following the specified bullet, e.g. `"- "`, continued
+    /// unchecked.
+    Checkbox(String),
+}
+
+impl ListMarker {
+    /// Match this marker at the start of the specified text, returning the matched length in bytes
+    /// and the marker which continues it.
+    fn parse(&self, text: &str) -> Option<(usize, String)> {
+        match self {
+            ListMarker::Bullet(bullet) => text
+                .starts_with(bullet.as_str())
+                .then(|| (bullet.len(), bullet.clone())),
+            ListMarker::Numbered(delimiter) => {
+                let digits = text.len()
+                    - text
+                        .trim_start_matches(|ch: char| ch.is_ascii_digit())
+                        .len();
+                let number: u64 = text[..digits].parse().ok()?;
+                let next_number = number.checked_add(1)?;
+
+                let suffix = format!("{delimiter} ");
+                text[digits..]
+                    .starts_with(&suffix)
+                    .then(|| (digits + suffix.len(), format!("{next_number}{suffix}")))
+            }
+            ListMarker::Checkbox(bullet) => {
+                let checkbox = text.strip_prefix(bullet.as_str())?;
+                ["[ ] ", "[x] ", "[X] "]
+                    .iter()
+                    .any(|state| checkbox.starts_with(state))
+                    .then(|| (bullet.len() + 4, format!("{bullet}[ ] ")))
+            }
+        }
+    }
+}
+
+/// Rules for continuing list items when [Key::Enter] inserts a newline after an item's marker:
+/// the new line starts a new item with the same indentation. Enter on an item without content
+/// removes its marker instead.
+///
+/// [Key::Enter]: crate::Key::Enter
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ListContinuation {
+    /// The markers recognized, tried in order.
+    pub markers: Vec<ListMarker>,
+}
+
+impl ListContinuation {
+    /// Create new list continuation rules recognizing the specified markers, tried in order.
+    pub fn new(markers: Vec<ListMarker>) -> Self {
+        Self { markers }
+    }
+
+    /// Find the list item at the start of the specified line whose marker ends at or before the
+    /// specified byte index, if any.
+    fn get_item(&self, line: &str, byte_index: usize) -> Option<ListItem> {
+        let prefix = &line[..byte_index];
+        let indent = prefix.len() - prefix.trim_start_matches([' ', '\t']).len();
+
+        self.markers.iter().find_map(|marker| {
+            let (length, continuation) = marker.parse(&prefix[indent..])?;
+
+            Some(ListItem {
+                prefix_len: indent + length,
+                has_content: !line[indent + length..].trim().is_empty(),
+                continuation: format!("{}{continuation}", &line[..indent]),
+            })
+        })
+    }
+
+    /// Get the length in bytes of the indentation and marker of the list item starting the
+    /// specified line, if any.
+    pub(crate) fn get_prefix_len(&self, line: &str) -> Option<usize> {
+        self.get_item(line, line.len()).map(|item| item.prefix_len)
+    }
+}
+
+impl Default for ListContinuation {
+    /// Markdown's bullet, numbered and checkbox lists.
+    fn default() -> Self {
+        let bullets = ["- ", "* ", "+ "];
+
+        let checkboxes = bullets
+            .iter()
+            .map(|bullet| ListMarker::Checkbox(bullet.to_string()));
+        let bullets = bullets
+            .iter()
+            .map(|bullet| ListMarker::Bullet(bullet.to_string()));
+        let numbered = ['.', ')'].into_iter().map(ListMarker::Numbered);
+
+        Self::new(checkboxes.chain(bullets).chain(numbered).collect())
+    }
+}
+
+/// A list item found at the start of a line.
+pub(crate) struct ListItem {
+    /// The length in bytes of the item's indentation and marker.
+    pub(crate) prefix_len: usize,
+
+    /// Whether the item has any non-whitespace content following its marker.
+    pub(crate) has_content: bool,
+
+    /// The indentation and marker which start the next item.
+    pub(crate) continuation: String,
+}
+
+impl<B: TextBuffer> Text<B> {
+    /// This editor's list continuation rules, if list continuation is enabled.
+    pub fn list_continuation(&self) -> Option<&ListContinuation> {
+        self.list_continuation.as_ref()
+    }
+
+    /// Update this editor's list continuation rules, or disable list continuation with `None`.
+    /// Markdown lists are continued by default.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_text::{Key, ListContinuation, ListMarker, Text};
+    ///
+    /// let mut text = Text::from("1. one", (6, 0), true);
+    ///
+    /// text.handle_input(Key::Enter);
+    /// assert_eq!("1. one\n2. ", text.value());
+    ///
+    /// // Enter on an empty item removes its marker
+    /// text.handle_input(Key::Enter);
+    /// assert_eq!("1. one\n", text.value());
+    ///
+    /// text.set_list_continuation(Some(ListContinuation::new(vec![
+    ///     ListMarker::Bullet("> ".to_string()),
+    /// ])));
+    /// text.insert_str("> quote");
+    /// text.handle_input(Key::Enter);
+    /// assert_eq!("1. one\n> quote\n> ", text.value());
+    ///
+    /// text.set_list_continuation(None);
+    /// text.handle_input(Key::Enter);
+    /// assert_eq!("1. one\n> quote\n> \n", text.value());
+    /// ```
+    pub fn set_list_continuation(&mut self, list_continuation: Option<ListContinuation>) {
+        self.list_continuation = list_continuation;
+    }
+
+    /// Get the list item on the specified line whose marker ends at or before the specified byte
+    /// index, if list continuation is enabled.
+    pub(crate) fn get_list_item(&self, line: &str, byte_index: usize) -> Option<ListItem> {
+        self.list_continuation.as_ref()?.get_item(line, byte_index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Key;
+
+    fn get_continuation(line: &str) -> Option<String> {
+        ListContinuation::default()
+            .get_item(line, line.len())
+            .map(|item| item.continuation)
+    }
+
+    #[test]
+    fn bullets() {
+        assert_eq!(Some("- ".to_string()), get_continuation("- a"));
+        assert_eq!(Some("* ".to_string()), get_continuation("* a"));
+        assert_eq!(Some("+ ".to_string()), get_continuation("+ a"));
+        assert_eq!(None, get_continuation("-a"));
+        assert_eq!(None, get_continuation("a - b"));
+    }
+
+    #[test]
+    fn numbered() {
+        assert_eq!(Some("2. ".to_string()), get_continuation("1. a"));
+        assert_eq!(Some("10) ".to_string()), get_continuation("9) a"));
+        assert_eq!(None, get_continuation("1.a"));
+        assert_eq!(None, get_continuation(". a"));
+        assert_eq!(None, get_continuation("99999999999999999999. a"));
+        assert_eq!(None, get_continuation("18446744073709551615. a"));
+        assert_eq!(
+            Some("18446744073709551615. ".to_string()),
+            get_continuation("18446744073709551614. a")
+        );
+    }
+
+    #[test]
+    fn numbered_at_maximum() {
+        let mut text = Text::from("18446744073709551615. a", (23, 0), true);
+
+        text.handle_input(Key::Enter);
+
+        assert_eq!("18446744073709551615. a\n", text.value());
+    }
+
+    #[test]
+    fn checkboxes() {
+        assert_eq!(Some("- [ ] ".to_string()), get_continuation("- [ ] a"));
+        assert_eq!(Some("* [ ] ".to_string()), get_continuation("* [x] a"));
+        assert_eq!(Some("- ".to_string()), get_continuation("- [y] a"));
+    }
+
+    #[test]
+    fn indentation() {
+        assert_eq!(Some("  - ".to_string()), get_continuation("  - a"));
+        assert_eq!(Some("\t3. ".to_string()), get_continuation("\t2. a"));
+        assert_eq!(Some(" - ".to_string()), get_continuation(" - a"));
+    }
+
+    #[test]
+    fn continue_item() {
+        let mut text = Text::from("  - [x] done", (12, 0), true);
+
+        text.handle_input(Key::Enter);
+
+        assert_eq!("  - [x] done\n  - [ ] ", text.value());
+        assert_eq!((8, 1), text.cursor());
+    }
+
+    #[test]
+    fn continue_item_mid_line() {
+        let mut text = Text::from("1. onetwo", (6, 0), true);
+
+        text.handle_input(Key::Enter);
+
+        assert_eq!("1. one\n2. two", text.value());
+        assert_eq!((3, 1), text.cursor());
+    }
+
+    #[test]
+    fn no_continuation_before_marker() {
+        let mut text = Text::from("- abc", (1, 0), true);
+
+        text.handle_input(Key::Enter);
+
+        assert_eq!("-\n abc", text.value());
+    }
+
+    #[test]
+    fn empty_item_ends_list() {
+        let mut text = Text::from("- a\n  - ", (4, 1), true);
+
+        text.handle_input(Key::Enter);
+        assert_eq!("- a\n", text.value());
+        assert_eq!((0, 1), text.cursor());
+
+        text.handle_input(Key::Undo);
+        assert_eq!("- a\n  - ", text.value());
+        assert_eq!((4, 1), text.cursor());
+    }
+
+    #[test]
+    fn disabled() {
+        let mut text = Text::from("- a", (3, 0), true);
+        text.set_list_continuation(None);
+
+        text.handle_input(Key::Enter);
+
+        assert_eq!("- a\n", text.value());
+        assert_eq!(None, text.list_continuation());
+    }
+}
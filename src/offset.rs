@@ -0,0 +1,338 @@
+//! Conversions between positions and absolute offsets into an editor's value.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{Position, Text, TextBuffer};
+
+/// A unit in which an absolute offset into an editor's [Text::value] is measured. Each `\n`
+/// joining two lines counts as a single unit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OffsetUnit {
+    /// UTF-8 bytes, as used by Rust strings.
+    Byte,
+
+    /// Unicode scalar values, as used by Rust's `char`.
+    Char,
+
+    /// UTF-16 code units, as used by the Language Server Protocol and JavaScript strings.
+    Utf16,
+
+    /// Extended grapheme clusters, as used by [Position] columns.
+    Grapheme,
+}
+
+impl OffsetUnit {
+    /// Measure the specified text's length in this unit.
+    fn measure(self, text: &str) -> usize {
+        match self {
+            OffsetUnit::Byte => text.len(),
+            OffsetUnit::Char => text.chars().count(),
+            OffsetUnit::Utf16 => text.encode_utf16().count(),
+            OffsetUnit::Grapheme => text.graphemes(true).count(),
+        }
+    }
+}
+
+impl<B: TextBuffer> Text<B> {
+    /// The offset of the specified position in this editor's [Text::value], measured in the
+    /// specified unit. The position will be clamped to the editor's current value.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_text::{OffsetUnit, Position, Text};
+    ///
+    /// let text = Text::from("a😀b\nc", (0, 0), true);
+    /// let position = Position { line: 1, column: 0 };
+    ///
+    /// assert_eq!(7, text.offset(position, OffsetUnit::Byte));
+    /// assert_eq!(4, text.offset(position, OffsetUnit::Char));
+    /// assert_eq!(5, text.offset(position, OffsetUnit::Utf16));
+    /// assert_eq!(4, text.offset(position, OffsetUnit::Grapheme));
+    /// ```
+    pub fn offset(&self, position: impl Into<Position>, unit: OffsetUnit) -> usize {
+        let (column, line) = self.clamp_position(position.into().into());
+
+        let preceding: usize = self
+            .lines()
+            .take(line)
+            .map(|line| unit.measure(&line) + 1)
+            .sum();
+
+        let line = self.buffer.line(line);
+        preceding + unit.measure(&line[..crate::get_byte_index(&line, column)])
+    }
+
+    /// The position of the specified offset in this editor's [Text::value], measured in the
+    /// specified unit, or `None` if the offset is beyond the value's end. An offset within a
+    /// grapheme cluster maps to the cluster's start.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_text::{OffsetUnit, Position, Text};
+    ///
+    /// let text = Text::from("a😀b\nc", (0, 0), true);
+    ///
+    /// let after_emoji = Position { line: 0, column: 2 };
+    /// assert_eq!(Some(after_emoji), text.position_at_offset(3, OffsetUnit::Utf16));
+    ///
+    /// let within_emoji = Position { line: 0, column: 1 };
+    /// assert_eq!(Some(within_emoji), text.position_at_offset(2, OffsetUnit::Utf16));
+    /// assert_eq!(None, text.position_at_offset(7, OffsetUnit::Utf16));
+    /// ```
+    pub fn position_at_offset(&self, mut offset: usize, unit: OffsetUnit) -> Option<Position> {
+        for (line_index, line) in self.lines().enumerate() {
+            let length = unit.measure(&line);
+            if offset > length {
+                // Skip the line and its trailing newline
+                offset -= length + 1;
+                continue;
+            }
+
+            let mut line_offset = 0;
+            let column = line
+                .graphemes(true)
+                .take_while(|grapheme| {
+                    line_offset += unit.measure(grapheme);
+                    line_offset <= offset
+                })
+                .count();
+
+            return Some(Position {
+                line: line_index,
+                column,
+            });
+        }
+
+        None
+    }
+
+    /// The byte offset of the specified position in this editor's [Text::value], as by
+    /// [Text::offset].
+    pub fn byte_offset(&self, position: impl Into<Position>) -> usize {
+        self.offset(position, OffsetUnit::Byte)
+    }
+
+    /// The character offset of the specified position in this editor's [Text::value], as by
+    /// [Text::offset].
+    pub fn char_offset(&self, position: impl Into<Position>) -> usize {
+        self.offset(position, OffsetUnit::Char)
+    }
+
+    /// The UTF-16 code unit offset of the specified position in this editor's [Text::value], as by
+    /// [Text::offset].
+    pub fn utf16_offset(&self, position: impl Into<Position>) -> usize {
+        self.offset(position, OffsetUnit::Utf16)
+    }
+
+    /// The position of the specified byte offset in this editor's [Text::value], as by
+    /// [Text::position_at_offset].
+    pub fn position_at_byte_offset(&self, offset: usize) -> Option<Position> {
+        self.position_at_offset(offset, OffsetUnit::Byte)
+    }
+
+    /// The position of the specified character offset in this editor's [Text::value], as by
+    /// [Text::position_at_offset].
+    pub fn position_at_char_offset(&self, offset: usize) -> Option<Position> {
+        self.position_at_offset(offset, OffsetUnit::Char)
+    }
+
+    /// The position of the specified UTF-16 code unit offset in this editor's [Text::value], as by
+    /// [Text::position_at_offset].
+    pub fn position_at_utf16_offset(&self, offset: usize) -> Option<Position> {
+        self.position_at_offset(offset, OffsetUnit::Utf16)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UNITS: [OffsetUnit; 4] = [
+        OffsetUnit::Byte,
+        OffsetUnit::Char,
+        OffsetUnit::Utf16,
+        OffsetUnit::Grapheme,
+    ];
+
+    #[test]
+    fn byte_offset() {
+        let text = Text::from("ab\ne\u{301}x\n", (0, 0), true);
+
+        assert_eq!(0, text.byte_offset((0, 0)));
+        assert_eq!(2, text.byte_offset((2, 0)));
+        assert_eq!(3, text.byte_offset((0, 1)));
+        assert_eq!(6, text.byte_offset((1, 1)));
+        assert_eq!(8, text.byte_offset((0, 2)));
+        assert_eq!(8, text.byte_offset((9, 9)));
+    }
+
+    #[test]
+    fn char_offset() {
+        let text = Text::from("ab\ne\u{301}x\n", (0, 0), true);
+
+        assert_eq!(2, text.char_offset((2, 0)));
+        assert_eq!(3, text.char_offset((0, 1)));
+        assert_eq!(5, text.char_offset((1, 1)));
+        assert_eq!(7, text.char_offset((0, 2)));
+    }
+
+    #[test]
+    fn utf16_offset() {
+        let text = Text::from("日😀\n😀x", (0, 0), true);
+
+        assert_eq!(1, text.utf16_offset((1, 0)));
+        assert_eq!(3, text.utf16_offset((2, 0)));
+        assert_eq!(4, text.utf16_offset((0, 1)));
+        assert_eq!(6, text.utf16_offset((1, 1)));
+        assert_eq!(7, text.utf16_offset((2, 1)));
+    }
+
+    #[test]
+    fn grapheme_offset() {
+        let text = Text::from("ab\ne\u{301}x\n", (0, 0), true);
+
+        assert_eq!(3, text.offset((0, 1), OffsetUnit::Grapheme));
+        assert_eq!(4, text.offset((1, 1), OffsetUnit::Grapheme));
+        assert_eq!(6, text.offset((0, 2), OffsetUnit::Grapheme));
+    }
+
+    #[test]
+    fn position_at_byte_offset() {
+        let text = Text::from("ab\ne\u{301}x\n", (0, 0), true);
+
+        assert_eq!(
+            Some(Position { line: 0, column: 0 }),
+            text.position_at_byte_offset(0)
+        );
+        assert_eq!(
+            Some(Position { line: 0, column: 2 }),
+            text.position_at_byte_offset(2)
+        );
+        assert_eq!(
+            Some(Position { line: 1, column: 0 }),
+            text.position_at_byte_offset(3)
+        );
+        assert_eq!(
+            Some(Position { line: 1, column: 0 }),
+            text.position_at_byte_offset(5)
+        );
+        assert_eq!(
+            Some(Position { line: 1, column: 1 }),
+            text.position_at_byte_offset(6)
+        );
+        assert_eq!(
+            Some(Position { line: 2, column: 0 }),
+            text.position_at_byte_offset(8)
+        );
+        assert_eq!(None, text.position_at_byte_offset(9));
+    }
+
+    #[test]
+    fn position_at_char_offset() {
+        let text = Text::from("ab\ne\u{301}x\n", (0, 0), true);
+
+        assert_eq!(
+            Some(Position { line: 0, column: 2 }),
+            text.position_at_char_offset(2)
+        );
+        assert_eq!(
+            Some(Position { line: 1, column: 0 }),
+            text.position_at_char_offset(3)
+        );
+        assert_eq!(
+            Some(Position { line: 1, column: 0 }),
+            text.position_at_char_offset(4)
+        );
+        assert_eq!(
+            Some(Position { line: 1, column: 1 }),
+            text.position_at_char_offset(5)
+        );
+        assert_eq!(
+            Some(Position { line: 2, column: 0 }),
+            text.position_at_char_offset(7)
+        );
+        assert_eq!(None, text.position_at_char_offset(8));
+    }
+
+    #[test]
+    fn position_at_utf16_offset() {
+        let text = Text::from("日😀\n😀x", (0, 0), true);
+
+        assert_eq!(
+            Some(Position { line: 0, column: 1 }),
+            text.position_at_utf16_offset(1)
+        );
+        assert_eq!(
+            Some(Position { line: 0, column: 1 }),
+            text.position_at_utf16_offset(2)
+        );
+        assert_eq!(
+            Some(Position { line: 0, column: 2 }),
+            text.position_at_utf16_offset(3)
+        );
+        assert_eq!(
+            Some(Position { line: 1, column: 0 }),
+            text.position_at_utf16_offset(4)
+        );
+        assert_eq!(
+            Some(Position { line: 1, column: 2 }),
+            text.position_at_utf16_offset(7)
+        );
+        assert_eq!(None, text.position_at_utf16_offset(8));
+    }
+
+    #[test]
+    fn position_at_grapheme_offset() {
+        let text = Text::from("ab\ne\u{301}x\n", (0, 0), true);
+
+        assert_eq!(
+            Some(Position { line: 1, column: 1 }),
+            text.position_at_offset(4, OffsetUnit::Grapheme)
+        );
+        assert_eq!(None, text.position_at_offset(7, OffsetUnit::Grapheme));
+    }
+
+    #[test]
+    fn offset_single_line() {
+        let text = Text::from("a\nb", (0, 0), false);
+
+        assert_eq!(2, text.byte_offset((2, 0)));
+        assert_eq!(
+            Some(Position { line: 0, column: 1 }),
+            text.position_at_byte_offset(1)
+        );
+    }
+
+    #[test]
+    fn offset_round_trip() {
+        let text = Text::from("日本\n\u{1F600}e\u{301}\n\nyz", (0, 0), true);
+
+        for unit in UNITS {
+            for line in 0..4 {
+                for column in 0..=2 {
+                    let position = Position::from(text.clamp_position((column, line)));
+
+                    let offset = text.offset(position, unit);
+                    assert_eq!(Some(position), text.position_at_offset(offset, unit));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn offset_matches_value() {
+        let text = Text::from("日本\n\u{1F600}e\u{301}\n\nyz", (0, 0), true);
+        let value = text.value();
+        let position = Position { line: 3, column: 1 };
+
+        assert_eq!(value.find('z').unwrap(), text.byte_offset(position));
+        assert_eq!(
+            value.chars().position(|ch| ch == 'z').unwrap(),
+            text.char_offset(position)
+        );
+        assert_eq!(
+            value.encode_utf16().count() - 1,
+            text.utf16_offset(position)
+        );
+    }
+}
@@ -0,0 +1,183 @@
+//! Positions and ranges within an editor's value.
+
+/// A position within an editor's value. Columns are measured in grapheme clusters. Positions are
+/// ordered by line, then column.
+///
+/// Tuples convert to and from positions in (column, line) order for compatibility. Prefer naming
+/// the fields to avoid mixing up the order.
+///
+/// # Examples
+/// ```
+/// use tty_text::Position;
+///
+/// let position = Position { line: 1, column: 4 };
+///
+/// assert_eq!(1, position.line);
+/// assert_eq!(4, position.column);
+/// assert_eq!(position, Position::from((4, 1)));
+/// assert!(position < Position { line: 2, column: 0 });
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Position {
+    /// The zero-based line index.
+    pub line: usize,
+
+    /// The zero-based column within the line, in grapheme clusters.
+    pub column: usize,
+}
+
+impl From<(usize, usize)> for Position {
+    /// Convert from a (column, line) tuple.
+    fn from((column, line): (usize, usize)) -> Self {
+        Self { line, column }
+    }
+}
+
+impl From<Position> for (usize, usize) {
+    /// Convert to a (column, line) tuple.
+    fn from(position: Position) -> Self {
+        (position.column, position.line)
+    }
+}
+
+impl PartialEq<(usize, usize)> for Position {
+    fn eq(&self, other: &(usize, usize)) -> bool {
+        *self == Position::from(*other)
+    }
+}
+
+impl PartialEq<Position> for (usize, usize) {
+    fn eq(&self, other: &Position) -> bool {
+        Position::from(*self) == *other
+    }
+}
+
+/// A range between two positions within an editor's value, from an inclusive start to an
+/// exclusive end.
+///
+/// # Examples
+/// ```
+/// use tty_text::{Position, Range};
+///
+/// let range = Range::new(Position { line: 1, column: 0 }, Position { line: 0, column: 2 });
+///
+/// assert_eq!(Position { line: 0, column: 2 }, range.start);
+/// assert_eq!(Position { line: 1, column: 0 }, range.end);
+/// assert!(range.contains(Position { line: 0, column: 5 }));
+/// assert!(!range.contains(Position { line: 1, column: 0 }));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Range {
+    /// The range's start, which is at or before its end.
+    pub start: Position,
+
+    /// The range's end.
+    pub end: Position,
+}
+
+impl Range {
+    /// Create a new range between the specified positions, which may be in either order.
+    pub fn new(a: impl Into<Position>, b: impl Into<Position>) -> Self {
+        let (a, b) = (a.into(), b.into());
+
+        Self {
+            start: std::cmp::min(a, b),
+            end: std::cmp::max(a, b),
+        }
+    }
+
+    /// Whether this range contains no text.
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Whether the specified position is within this range.
+    pub fn contains(&self, position: impl Into<Position>) -> bool {
+        let position = position.into();
+        self.start <= position && position < self.end
+    }
+}
+
+impl<P: Into<Position>> From<(P, P)> for Range {
+    /// Convert from a pair of positions, which may be in either order.
+    fn from((a, b): (P, P)) -> Self {
+        Self::new(a, b)
+    }
+}
+
+impl From<std::ops::Range<Position>> for Range {
+    fn from(range: std::ops::Range<Position>) -> Self {
+        Self::new(range.start, range.end)
+    }
+}
+
+impl From<Range> for ((usize, usize), (usize, usize)) {
+    /// Convert to a pair of (column, line) tuples.
+    fn from(range: Range) -> Self {
+        (range.start.into(), range.end.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn position_ordering() {
+        assert!(Position { line: 0, column: 5 } < Position { line: 1, column: 0 });
+        assert!(Position { line: 1, column: 1 } > Position { line: 1, column: 0 });
+        assert_eq!(
+            Position { line: 2, column: 3 },
+            Position { line: 2, column: 3 }
+        );
+    }
+
+    #[test]
+    fn position_tuple_conversions() {
+        let position: Position = (3, 1).into();
+        assert_eq!(Position { line: 1, column: 3 }, position);
+
+        let tuple: (usize, usize) = position.into();
+        assert_eq!((3, 1), tuple);
+
+        assert_eq!((3, 1), position);
+        assert_eq!(position, (3, 1));
+    }
+
+    #[test]
+    fn range_ordered() {
+        let range = Range::new((2, 1), (0, 0));
+
+        assert_eq!(Position { line: 0, column: 0 }, range.start);
+        assert_eq!(Position { line: 1, column: 2 }, range.end);
+        assert!(!range.is_empty());
+    }
+
+    #[test]
+    fn range_conversions() {
+        let range: Range = ((1, 0), (2, 1)).into();
+        assert_eq!(
+            Range::new(
+                Position { line: 0, column: 1 },
+                Position { line: 1, column: 2 }
+            ),
+            range
+        );
+
+        let range: Range =
+            (Position { line: 0, column: 1 }..Position { line: 1, column: 2 }).into();
+        assert_eq!(((1, 0), (2, 1)), range.into());
+    }
+
+    #[test]
+    fn range_contains() {
+        let range = Range::new((1, 0), (1, 1));
+
+        assert!(!range.contains((0, 0)));
+        assert!(range.contains((1, 0)));
+        assert!(range.contains((9, 0)));
+        assert!(range.contains((0, 1)));
+        assert!(!range.contains((1, 1)));
+        assert!(Range::new((1, 0), (1, 0)).is_empty());
+    }
+}
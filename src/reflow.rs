@@ -0,0 +1,462 @@
+//! Hard wrapping of paragraphs to a fixed width.
+
+use std::borrow::Cow;
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::{history::EditKind, ListContinuation, Text, TextBuffer};
+
+impl<B: TextBuffer> Text<B> {
+    /// Re-wrap the paragraph under the cursor, or every paragraph touched by the selection, so no
+    /// line exceeds the specified width in display columns where possible. Paragraphs are
+    /// separated by blank lines and list items, as recognized by the editor's [ListContinuation]
+    /// rules; a list item's continuation lines are given a hanging indent under its text, and
+    /// other paragraphs keep their first line's indentation.
+    /// Words wider than the width are left on their own line. The cursor stays with the same
+    /// character, and the change is undone as a single edit.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_text::Text;
+    ///
+    /// let mut text = Text::from(" - one two three\nfour\n\nfive", (2, 1), true);
+    ///
+    /// text.reflow(10);
+    ///
+    /// assert_eq!(" - one two\n   three\n   four\n\nfive", text.value());
+    /// assert_eq!((5, 2), text.cursor());
+    /// ```
+    pub fn reflow(&mut self, width: usize) {
+        if !self.multi_line {
+            return;
+        }
+
+        let (first_line, last_line) = match self.selection() {
+            Some(selection) => (selection.start.line, selection.end.line),
+            None => match self.get_paragraph_lines(self.cursor.1) {
+                Some(lines) => lines,
+                None => return,
+            },
+        };
+
+        let original_lines: Vec<Cow<str>> = (first_line..=last_line)
+            .map(|line_index| self.buffer.line(line_index))
+            .collect();
+        let original: Vec<&str> = original_lines.iter().map(|line| line.as_ref()).collect();
+
+        let original_value = original.join("\n");
+        let list = self.list_continuation.as_ref();
+        let reflowed_value = reflow_lines(&original, width, list).join("\n");
+        if original_value == reflowed_value {
+            return;
+        }
+
+        // Find the cursor's place relative to the non-whitespace content it follows
+        let cursor = self.get_raw_position(self.cursor);
+        let cursor_offset = (first_line..cursor.1)
+            .map(|line_index| self.buffer.line_len(line_index) + 1)
+            .sum::<usize>()
+            + cursor.0;
+        let anchor = get_content_anchor(&original_value[..cursor_offset]);
+
+        self.transact(EditKind::Other, |text| {
+            text.anchor = None;
+
+            let end = (text.buffer.line_len(last_line), last_line);
+            text.splice((0, first_line), end, &reflowed_value);
+
+            let offset = find_content_anchor(&reflowed_value, anchor);
+            let prefix = &reflowed_value[..offset];
+            let line_index = first_line + prefix.matches('\n').count();
+            let byte_index = prefix.len() - prefix.rfind('\n').map_or(0, |index| index + 1);
+
+            text.cursor = text.get_position((byte_index, line_index));
+            text.update_preferred_column();
+        });
+    }
+
+    /// Get the first and last line indices of the paragraph containing the specified line, or
+    /// `None` if the line is blank.
+    fn get_paragraph_lines(&self, line_index: usize) -> Option<(usize, usize)> {
+        let is_blank = |index: usize| self.buffer.line(index).trim().is_empty();
+        let is_item =
+            |index: usize| is_list_item(&self.buffer.line(index), self.list_continuation());
+
+        if is_blank(line_index) {
+            return None;
+        }
+
+        let mut first_line = line_index;
+        while first_line > 0 && !is_item(first_line) && !is_blank(first_line - 1) {
+            first_line -= 1;
+        }
+
+        let mut last_line = line_index;
+        while last_line + 1 < self.buffer.line_count()
+            && !is_blank(last_line + 1)
+            && !is_item(last_line + 1)
+        {
+            last_line += 1;
+        }
+
+        Some((first_line, last_line))
+    }
+}
+
+/// Whether the specified line starts a list item under the specified list continuation rules.
+fn is_list_item(line: &str, list: Option<&ListContinuation>) -> bool {
+    list.is_some_and(|list| list.get_prefix_len(line).is_some())
+}
+
+/// Re-wrap each paragraph in the specified lines to the specified width, preserving blank lines.
+/// List items are recognized by the specified list continuation rules, if any.
+fn reflow_lines(lines: &[&str], width: usize, list: Option<&ListContinuation>) -> Vec<String> {
+    let mut reflowed = Vec::new();
+    let mut paragraph = Vec::new();
+
+    for &line in lines {
+        // Blank lines and list items end the preceding paragraph
+        let is_blank = line.trim().is_empty();
+        if (is_blank || is_list_item(line, list)) && !paragraph.is_empty() {
+            reflowed.extend(reflow_paragraph(&paragraph, width, list));
+            paragraph.clear();
+        }
+
+        if is_blank {
+            reflowed.push(line.to_string());
+        } else {
+            paragraph.push(line);
+        }
+    }
+
+    if !paragraph.is_empty() {
+        reflowed.extend(reflow_paragraph(&paragraph, width, list));
+    }
+
+    reflowed
+}
+
+/// Re-wrap the specified non-blank paragraph lines to the specified width. A paragraph starting
+/// with a list item under the specified list continuation rules is given a hanging indent.
+fn reflow_paragraph(lines: &[&str], width: usize, list: Option<&ListContinuation>) -> Vec<String> {
+    let (first_prefix, continuation_prefix) =
+        match list.and_then(|list| list.get_prefix_len(lines[0])) {
+            Some(prefix_len) => {
+                // Align continuation lines under the item's text, after its indentation
+                let prefix = &lines[0][..prefix_len];
+                let marker = prefix.trim_start_matches([' ', '\t']);
+                let indent = &prefix[..prefix.len() - marker.len()];
+                (
+                    prefix.to_string(),
+                    indent.to_string() + &" ".repeat(marker.width()),
+                )
+            }
+            None => {
+                let content_start = lines[0].len() - lines[0].trim_start().len();
+                let indent = lines[0][..content_start].to_string();
+                (indent.clone(), indent)
+            }
+        };
+
+    let first_content = &lines[0][first_prefix.len()..];
+    let words = std::iter::once(first_content)
+        .chain(lines[1..].iter().copied())
+        .flat_map(|line| line.split_whitespace());
+
+    let mut reflowed = vec![first_prefix];
+    let mut line_has_words = false;
+
+    for word in words {
+        let line = reflowed.last_mut().expect("reflowed paragraph has a line");
+        let word_width = word.width();
+
+        if line_has_words && line.width() + 1 + word_width > width {
+            reflowed.push(continuation_prefix.clone() + word);
+            continue;
+        }
+
+        if line_has_words {
+            line.push(' ');
+        }
+
+        line.push_str(word);
+        line_has_words = true;
+    }
+
+    reflowed
+}
+
+/// Describes a position by the number of non-whitespace graphemes preceding it and whether it
+/// immediately follows one, so it can be found again after whitespace is rearranged.
+#[derive(Clone, Copy)]
+struct ContentAnchor {
+    /// The number of non-whitespace graphemes preceding the position.
+    count: usize,
+
+    /// Whether the position immediately follows a non-whitespace grapheme.
+    follows_content: bool,
+}
+
+/// Get the content anchor for the position at the end of the specified text.
+fn get_content_anchor(prefix: &str) -> ContentAnchor {
+    let mut anchor = ContentAnchor {
+        count: 0,
+        follows_content: false,
+    };
+
+    for grapheme in prefix.graphemes(true) {
+        anchor.follows_content = !grapheme.trim().is_empty();
+        if anchor.follows_content {
+            anchor.count += 1;
+        }
+    }
+
+    anchor
+}
+
+/// Find the byte offset in the specified text matching the specified content anchor. A position
+/// which followed content stays directly after it; otherwise it moves directly before the next
+/// content.
+fn find_content_anchor(text: &str, anchor: ContentAnchor) -> usize {
+    let mut count = 0;
+
+    for (index, grapheme) in text.grapheme_indices(true) {
+        if count == anchor.count && (anchor.follows_content || !grapheme.trim().is_empty()) {
+            return index;
+        }
+
+        if !grapheme.trim().is_empty() {
+            count += 1;
+        }
+    }
+
+    text.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Key;
+
+    macro_rules! svec {
+        ($($x:expr),*) => (vec![$($x.to_string()),*]);
+    }
+
+    #[test]
+    fn reflow_paragraph_plain() {
+        let lines = ["one two three four five"];
+
+        assert_eq!(
+            svec!["one two", "three", "four five"],
+            reflow_paragraph(&lines, 9, Some(&ListContinuation::default()))
+        );
+    }
+
+    #[test]
+    fn reflow_paragraph_joins_lines() {
+        let lines = ["one", "two", "three"];
+
+        assert_eq!(
+            svec!["one two three"],
+            reflow_paragraph(&lines, 20, Some(&ListContinuation::default()))
+        );
+    }
+
+    #[test]
+    fn reflow_paragraph_indented() {
+        let lines = ["  one two three", "four"];
+
+        assert_eq!(
+            svec!["  one two", "  three", "  four"],
+            reflow_paragraph(&lines, 10, Some(&ListContinuation::default()))
+        );
+    }
+
+    #[test]
+    fn reflow_paragraph_bullet() {
+        let lines = [" - one two three four"];
+
+        assert_eq!(
+            svec![" - one two", "   three", "   four"],
+            reflow_paragraph(&lines, 10, Some(&ListContinuation::default()))
+        );
+    }
+
+    #[test]
+    fn reflow_paragraph_list_markers() {
+        let list = ListContinuation::default();
+
+        assert_eq!(
+            svec!["* one two", "  three"],
+            reflow_paragraph(&["* one two three"], 9, Some(&list))
+        );
+        assert_eq!(
+            svec!["  10. one", "      two"],
+            reflow_paragraph(&["  10. one two"], 9, Some(&list))
+        );
+        assert_eq!(
+            svec!["- [ ] one", "      two"],
+            reflow_paragraph(&["- [ ] one two"], 9, Some(&list))
+        );
+    }
+
+    #[test]
+    fn reflow_paragraph_list_disabled() {
+        assert_eq!(
+            svec!["- one", "two"],
+            reflow_paragraph(&["- one two"], 5, None)
+        );
+    }
+
+    #[test]
+    fn reflow_paragraph_long_word() {
+        let lines = ["a abcdefghij b"];
+
+        assert_eq!(
+            svec!["a", "abcdefghij", "b"],
+            reflow_paragraph(&lines, 5, Some(&ListContinuation::default()))
+        );
+    }
+
+    #[test]
+    fn reflow_paragraph_wide_characters() {
+        let lines = ["日本 語で す"];
+
+        assert_eq!(
+            svec!["日本", "語で す"],
+            reflow_paragraph(&lines, 7, Some(&ListContinuation::default()))
+        );
+    }
+
+    #[test]
+    fn reflow_lines_paragraphs() {
+        let lines = ["one two", "", " - three four", " - five", "   six"];
+
+        assert_eq!(
+            svec!["one two", "", " - three", "   four", " - five", "   six"],
+            reflow_lines(&lines, 8, Some(&ListContinuation::default()))
+        );
+    }
+
+    #[test]
+    fn reflow_lines_list_items() {
+        let list = ListContinuation::default();
+
+        let lines = ["- one", "- two", "* three"];
+        assert_eq!(
+            svec!["- one", "- two", "* three"],
+            reflow_lines(&lines, 20, Some(&list))
+        );
+
+        let lines = ["1. one", "2. two", "three"];
+        assert_eq!(
+            svec!["1. one", "2. two three"],
+            reflow_lines(&lines, 20, Some(&list))
+        );
+    }
+
+    #[test]
+    fn reflow_list_items() {
+        let mut text = Text::from("- one\n- two\n* three", (0, 1), true);
+
+        text.set_selection((0, 0), (0, 2));
+        text.reflow(20);
+
+        assert_eq!("- one\n- two\n* three", text.value());
+        assert!(!text.undo());
+    }
+
+    #[test]
+    fn reflow_numbered_item() {
+        let mut text = Text::from("1. one\n2. two three four", (0, 1), true);
+
+        text.reflow(10);
+
+        assert_eq!("1. one\n2. two\n   three\n   four", text.value());
+    }
+
+    #[test]
+    fn reflow_cursor_paragraph() {
+        let mut text = Text::from("one two\n\nthree four five\nsix\n\nseven", (0, 3), true);
+
+        text.reflow(10);
+
+        assert_eq!("one two\n\nthree four\nfive six\n\nseven", text.value());
+        assert_eq!((5, 3), text.cursor());
+    }
+
+    #[test]
+    fn reflow_cursor_bullet() {
+        let mut text = Text::from(" - one\n - two three four\n - five", (4, 1), true);
+
+        text.reflow(12);
+
+        assert_eq!(" - one\n - two three\n   four\n - five", text.value());
+        assert_eq!((4, 1), text.cursor());
+    }
+
+    #[test]
+    fn reflow_cursor_in_whitespace() {
+        let mut text = Text::from("one  two three", (4, 0), true);
+
+        text.reflow(8);
+
+        assert_eq!("one two\nthree", text.value());
+        assert_eq!((4, 0), text.cursor());
+    }
+
+    #[test]
+    fn reflow_cursor_after_word() {
+        let mut text = Text::from("one two three", (7, 0), true);
+
+        text.reflow(5);
+
+        assert_eq!("one\ntwo\nthree", text.value());
+        assert_eq!((3, 1), text.cursor());
+    }
+
+    #[test]
+    fn reflow_selection() {
+        let mut text = Text::from("a b c\n\nd e f\n\ng h i", (0, 0), true);
+
+        text.set_selection((1, 0), (1, 2));
+        text.reflow(3);
+
+        assert_eq!("a b\nc\n\nd e\nf\n\ng h i", text.value());
+        assert_eq!((1, 3), text.cursor());
+        assert_eq!(None, text.selection());
+    }
+
+    #[test]
+    fn reflow_blank_line() {
+        let mut text = Text::from("one two\n\nthree", (0, 1), true);
+
+        text.reflow(3);
+
+        assert_eq!("one two\n\nthree", text.value());
+        assert!(!text.undo());
+    }
+
+    #[test]
+    fn reflow_undo() {
+        let mut text = Text::from("one two three", (9, 0), true);
+
+        text.reflow(5);
+        assert_eq!("one\ntwo\nthree", text.value());
+        assert_eq!((1, 2), text.cursor());
+
+        text.handle_input(Key::Undo);
+        assert_eq!("one two three", text.value());
+        assert_eq!((9, 0), text.cursor());
+    }
+
+    #[test]
+    fn reflow_single_line() {
+        let mut text = Text::from("one two three", (0, 0), false);
+
+        text.reflow(5);
+
+        assert_eq!("one two three", text.value());
+    }
+}
@@ -0,0 +1,946 @@
+//! Finding and replacing text within an editor's value.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use regex::Regex;
+
+use crate::{history::EditKind, Key, Position, Range, Text, TextBuffer};
+
+/// Options controlling how a search query matches an editor's value.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SearchOptions {
+    /// Whether letters must match case exactly. Otherwise, letters match regardless of case.
+    pub case_sensitive: bool,
+
+    /// Whether matches must not be directly preceded or followed by a word character, i.e. a
+    /// letter, digit or underscore.
+    pub whole_word: bool,
+}
+
+impl SearchOptions {
+    /// Create new search options.
+    pub fn new(case_sensitive: bool, whole_word: bool) -> Self {
+        Self {
+            case_sensitive,
+            whole_word,
+        }
+    }
+}
+
+/// An incremental search in progress, started by [Key::Search].
+pub(crate) struct IncrementalSearch {
+    /// The query entered so far.
+    query: String,
+
+    /// The cursor in (columns, lines) when the search started, restored if it's cancelled.
+    origin_cursor: (usize, usize),
+
+    /// The selection anchor in (columns, lines) when the search started, if any.
+    origin_anchor: Option<(usize, usize)>,
+}
+
+impl<B: TextBuffer> Text<B> {
+    /// The options used by incremental searches started with [Key::Search].
+    pub fn search_options(&self) -> SearchOptions {
+        self.search_options
+    }
+
+    /// Update the options used by incremental searches started with [Key::Search]. If a search is
+    /// in progress, the cursor moves to the nearest match under the new options.
+    pub fn set_search_options(&mut self, options: SearchOptions) {
+        self.search_options = options;
+        self.jump_to_search_match();
+    }
+
+    /// The query of the incremental search in progress, if any, e.g. to render a search prompt.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_text::{Key, Range, Text};
+    ///
+    /// let mut text = Text::from("Hello,\nworld!", (0, 0), true);
+    ///
+    /// text.handle_input(Key::Search);
+    /// text.handle_input(Key::Char('o'));
+    /// text.handle_input(Key::Char('r'));
+    ///
+    /// assert_eq!(Some("or"), text.search_query());
+    /// assert_eq!(Some(Range::new((1, 1), (3, 1))), text.selection());
+    /// assert_eq!("Hello,\nworld!", text.value());
+    ///
+    /// // Enter accepts the match, leaving the cursor at its end
+    /// text.handle_input(Key::Enter);
+    /// assert_eq!(None, text.search_query());
+    /// assert_eq!(None, text.selection());
+    /// assert_eq!((3, 1), text.cursor());
+    /// ```
+    pub fn search_query(&self) -> Option<&str> {
+        self.search.as_ref().map(|search| search.query.as_str())
+    }
+
+    /// Find every non-overlapping occurrence of the specified query in this editor's value, in
+    /// order. Queries may span lines with `\n`. Matches which would split a grapheme cluster are
+    /// skipped.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_text::{Range, SearchOptions, Text};
+    ///
+    /// let text = Text::from("One one\nphone", (0, 0), true);
+    ///
+    /// assert_eq!(
+    ///     vec![
+    ///         Range::new((0, 0), (3, 0)),
+    ///         Range::new((4, 0), (7, 0)),
+    ///         Range::new((2, 1), (5, 1)),
+    ///     ],
+    ///     text.find_all("one", SearchOptions::default()),
+    /// );
+    ///
+    /// let options = SearchOptions::new(true, true);
+    /// assert_eq!(vec![Range::new((4, 0), (7, 0))], text.find_all("one", options));
+    /// ```
+    pub fn find_all(&self, query: &str, options: SearchOptions) -> Vec<Range> {
+        let value = self.value();
+        let offsets = OffsetMap::new(&value);
+
+        find_matches(&value, query, options)
+            .into_iter()
+            .filter_map(|range| offsets.range(range))
+            .collect()
+    }
+
+    /// Select the first match of the specified query after the cursor or selection, wrapping around
+    /// to the value's start if there are none. Returns the selected match, or `None` if there are no
+    /// matches, in which case the cursor is unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_text::{Range, SearchOptions, Text};
+    ///
+    /// let mut text = Text::from("a1 a2 a3", (3, 0), false);
+    ///
+    /// text.find_next("a", SearchOptions::default());
+    /// assert_eq!(Some(Range::new((3, 0), (4, 0))), text.selection());
+    ///
+    /// text.find_next("a", SearchOptions::default());
+    /// assert_eq!(Some(Range::new((6, 0), (7, 0))), text.selection());
+    ///
+    /// text.find_next("a", SearchOptions::default());
+    /// assert_eq!(Some(Range::new((0, 0), (1, 0))), text.selection());
+    /// ```
+    pub fn find_next(&mut self, query: &str, options: SearchOptions) -> Option<Range> {
+        let matches = self.find_all(query, options);
+        let origin = match self.selection() {
+            Some(selection) => selection.end,
+            None => self.cursor(),
+        };
+
+        let next = matches
+            .iter()
+            .find(|range| range.start >= origin)
+            .or(matches.first())
+            .copied()?;
+
+        self.select_match(next);
+        Some(next)
+    }
+
+    /// Select the last match of the specified query before the cursor or selection, wrapping
+    /// around to the value's end if there are none. Returns the selected match, or `None` if there
+    /// are no matches, in which case the cursor is unchanged.
+    pub fn find_previous(&mut self, query: &str, options: SearchOptions) -> Option<Range> {
+        let matches = self.find_all(query, options);
+        let origin = match self.selection() {
+            Some(selection) => selection.start,
+            None => self.cursor(),
+        };
+
+        let previous = matches
+            .iter()
+            .rev()
+            .find(|range| range.start < origin)
+            .or(matches.last())
+            .copied()?;
+
+        self.select_match(previous);
+        Some(previous)
+    }
+
+    /// Find every match of the specified regular expression in this editor's value, in order. The
+    /// expression runs over the whole value with lines separated by `\n`, so use `(?m)` for `^`
+    /// and `$` to match at line boundaries. Matches which would split a grapheme cluster are
+    /// skipped.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_text::{Range, Regex, Text};
+    ///
+    /// let text = Text::from("id: 12\nid: 345", (0, 0), true);
+    /// let pattern = Regex::new(r"\d+").unwrap();
+    ///
+    /// assert_eq!(
+    ///     vec![Range::new((4, 0), (6, 0)), Range::new((4, 1), (7, 1))],
+    ///     text.find_regex(&pattern),
+    /// );
+    /// ```
+    pub fn find_regex(&self, pattern: &Regex) -> Vec<Range> {
+        let value = self.value();
+        let offsets = OffsetMap::new(&value);
+
+        pattern
+            .find_iter(&value)
+            .filter_map(|found| offsets.range(found.range()))
+            .collect()
+    }
+
+    /// Replace the first match of the specified regular expression at or after the selection's
+    /// start or cursor, wrapping around to the value's start if there are none, as a single
+    /// undoable edit. The replacement may refer to capture groups as by [Regex::replace], e.g.
+    /// `$1` or `${name}`.
+    ///
+    /// The cursor moves to the end of the replacement and any selection is cleared. Returns the
+    /// replacement's range, or `None` if there are no matches, in which case nothing changes.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_text::{Range, Regex, Text};
+    ///
+    /// let mut text = Text::from("a=1, b=2", (1, 0), false);
+    /// let pattern = Regex::new(r"(\w)=(\d)").unwrap();
+    ///
+    /// let replaced = text.replace_regex(&pattern, "$2:$1");
+    /// assert_eq!("a=1, 2:b", text.value());
+    /// assert_eq!(Some(Range::new((5, 0), (8, 0))), replaced);
+    /// assert_eq!((8, 0), text.cursor());
+    /// ```
+    pub fn replace_regex(&mut self, pattern: &Regex, replacement: &str) -> Option<Range> {
+        let origin = match self.selection() {
+            Some(selection) => selection.start,
+            None => self.cursor(),
+        };
+
+        let mut replacements = self.get_regex_replacements(pattern, replacement);
+        if replacements.is_empty() {
+            return None;
+        }
+
+        let index = replacements
+            .iter()
+            .position(|(range, _)| range.start >= origin)
+            .unwrap_or(0);
+
+        let (range, value) = replacements.swap_remove(index);
+        let (start, end) = (range.start.into(), range.end.into());
+        let mut replaced = None;
+
+        self.transact(EditKind::Other, |text| {
+            let raw_start = text.get_raw_position(start);
+
+            text.anchor = None;
+            text.replace_range_adjusting(start, end, &value);
+
+            text.cursor = text.get_position(crate::get_raw_end(raw_start, &value));
+            text.update_preferred_column();
+
+            replaced = Some(Range::new(start, text.cursor));
+        });
+
+        replaced
+    }
+
+    /// Replace every match of the specified regular expression as by [Text::replace_regex], as a
+    /// single undoable edit. The cursor and selection anchor are adjusted as by
+    /// [Text::replace_range]. Returns the number of matches replaced.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_text::{Regex, Text};
+    ///
+    /// let mut text = Text::from("one, two, three", (0, 0), true);
+    /// let pattern = Regex::new(r",\s*").unwrap();
+    ///
+    /// assert_eq!(2, text.replace_all_regex(&pattern, "\n"));
+    /// assert_eq!("one\ntwo\nthree", text.value());
+    /// assert_eq!(3, text.line_count());
+    /// ```
+    pub fn replace_all_regex(&mut self, pattern: &Regex, replacement: &str) -> usize {
+        let replacements = self.get_regex_replacements(pattern, replacement);
+        if replacements.is_empty() {
+            return 0;
+        }
+
+        self.transact(EditKind::Other, |text| {
+            // Replace from the end so earlier matches' positions remain valid
+            for (range, value) in replacements.iter().rev() {
+                text.replace_range_adjusting(range.start.into(), range.end.into(), value);
+            }
+        });
+
+        replacements.len()
+    }
+
+    /// Get the range of each match of the specified regular expression alongside its expanded,
+    /// normalized replacement.
+    fn get_regex_replacements(&self, pattern: &Regex, replacement: &str) -> Vec<(Range, String)> {
+        let value = self.value();
+        let offsets = OffsetMap::new(&value);
+
+        pattern
+            .captures_iter(&value)
+            .filter_map(|captures| {
+                let range = offsets.range(captures.get(0)?.range())?;
+
+                let mut expanded = String::new();
+                captures.expand(replacement, &mut expanded);
+
+                Some((range, crate::normalize_newlines(&expanded, self.multi_line)))
+            })
+            .collect()
+    }
+
+    /// Start an incremental search from the cursor, replacing any search in progress.
+    pub(crate) fn start_search(&mut self) {
+        self.search = Some(IncrementalSearch {
+            query: String::new(),
+            origin_cursor: self.cursor,
+            origin_anchor: self.anchor,
+        });
+    }
+
+    /// Handle the specified input as part of the incremental search in progress, if any. Returns
+    /// the input if it should instead be handled as normal, which accepts any search in progress.
+    pub(crate) fn handle_search_input(&mut self, input: Key) -> Option<Key> {
+        let Some(search) = self.search.as_mut() else {
+            return Some(input);
+        };
+
+        match input {
+            Key::Char(ch) => search.query.push(ch),
+            Key::Paste(value) => search
+                .query
+                .push_str(&crate::normalize_newlines(&value, self.multi_line)),
+            Key::Backspace => {
+                search.query.pop();
+            }
+            Key::Search | Key::Down => {
+                let query = search.query.clone();
+                self.find_next(&query, self.search_options);
+                return None;
+            }
+            Key::Up => {
+                let query = search.query.clone();
+                self.find_previous(&query, self.search_options);
+                return None;
+            }
+            Key::Enter => {
+                self.search = None;
+                self.clear_selection();
+                return None;
+            }
+            Key::Escape => {
+                self.restore_search_origin();
+                self.search = None;
+                return None;
+            }
+            input => {
+                self.search = None;
+                return Some(input);
+            }
+        }
+
+        self.jump_to_search_match();
+        None
+    }
+
+    /// Select the nearest match of the incremental search's query at or after the current match
+    /// or, if the query is empty, restore the search's original cursor. The cursor stays in place
+    /// if there are no matches.
+    fn jump_to_search_match(&mut self) {
+        let Some(search) = &self.search else {
+            return;
+        };
+
+        if search.query.is_empty() {
+            self.restore_search_origin();
+            return;
+        }
+
+        let query = search.query.clone();
+        let (cursor, anchor) = (self.cursor, self.anchor);
+
+        // Search from the current match's start so extending the query keeps it if still matching
+        if let Some(selection) = self.selection() {
+            self.cursor = selection.start.into();
+        }
+        self.anchor = None;
+
+        if self.find_next(&query, self.search_options).is_none() {
+            self.cursor = cursor;
+            self.anchor = anchor;
+        }
+    }
+
+    /// Restore the cursor and selection from when the incremental search in progress started.
+    fn restore_search_origin(&mut self) {
+        let Some(search) = &self.search else {
+            return;
+        };
+
+        let (cursor, anchor) = (search.origin_cursor, search.origin_anchor);
+        self.set_cursor(cursor);
+        self.anchor = anchor.map(|anchor| self.clamp_position(anchor));
+    }
+
+    /// Select the specified match, leaving the cursor at its end.
+    pub(crate) fn select_match(&mut self, range: Range) {
+        self.set_selection(range.start, range.end);
+    }
+}
+
+/// Find the byte ranges of every non-overlapping occurrence of the specified query in the
+/// specified value.
+fn find_matches(value: &str, query: &str, options: SearchOptions) -> Vec<std::ops::Range<usize>> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let mut matches = Vec::new();
+    let mut search_start = 0;
+
+    for (start, _) in value.char_indices() {
+        if start < search_start {
+            continue;
+        }
+
+        let Some(end) = match_at(value, start, query, options.case_sensitive) else {
+            continue;
+        };
+
+        if options.whole_word && !is_whole_word(value, start, end) {
+            continue;
+        }
+
+        matches.push(start..end);
+        search_start = end;
+    }
+
+    matches
+}
+
+/// Get the end of the specified query's match at the specified byte index in the value, if it
+/// matches there.
+fn match_at(value: &str, start: usize, query: &str, case_sensitive: bool) -> Option<usize> {
+    if case_sensitive {
+        return value[start..]
+            .starts_with(query)
+            .then_some(start + query.len());
+    }
+
+    let mut value_chars = value[start..].chars();
+    let mut end = start;
+
+    for query_char in query.chars() {
+        let value_char = value_chars.next()?;
+        let matches =
+            value_char == query_char || value_char.to_lowercase().eq(query_char.to_lowercase());
+
+        if !matches {
+            return None;
+        }
+
+        end += value_char.len_utf8();
+    }
+
+    Some(end)
+}
+
+/// Whether the specified byte range of the value isn't directly adjacent to a word character.
+fn is_whole_word(value: &str, start: usize, end: usize) -> bool {
+    let is_word_char = |ch: char| ch.is_alphanumeric() || ch == '_';
+
+    let preceded = value[..start].chars().next_back().is_some_and(is_word_char);
+    let followed = value[end..].chars().next().is_some_and(is_word_char);
+
+    !preceded && !followed
+}
+
+/// Converts byte offsets in an editor's value to positions.
+struct OffsetMap<'a> {
+    /// The editor's value.
+    value: &'a str,
+
+    /// The byte offset at which each line of the value starts.
+    line_starts: Vec<usize>,
+}
+
+impl<'a> OffsetMap<'a> {
+    /// Create a new map over the specified value.
+    fn new(value: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(value.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+
+        Self { value, line_starts }
+    }
+
+    /// Get the position of the specified byte offset, or `None` if it is within a grapheme
+    /// cluster.
+    fn position(&self, offset: usize) -> Option<Position> {
+        let line_index = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let line_start = self.line_starts[line_index];
+        let line_end = self
+            .line_starts
+            .get(line_index + 1)
+            .map_or(self.value.len(), |&next_start| next_start - 1);
+
+        let line = &self.value[line_start..line_end];
+        let column = get_column_index(line, offset - line_start)?;
+
+        Some(Position {
+            line: line_index,
+            column,
+        })
+    }
+
+    /// Get the range of positions of the specified byte range, or `None` if it starts or ends
+    /// within a grapheme cluster.
+    fn range(&self, range: std::ops::Range<usize>) -> Option<Range> {
+        Some(Range::new(
+            self.position(range.start)?,
+            self.position(range.end)?,
+        ))
+    }
+}
+
+/// Get the grapheme column at the specified byte index in the line, or `None` if the index is
+/// within a grapheme cluster.
+fn get_column_index(line: &str, byte_index: usize) -> Option<usize> {
+    if byte_index == line.len() {
+        return Some(line.graphemes(true).count());
+    }
+
+    line.grapheme_indices(true)
+        .position(|(index, _)| index == byte_index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INSENSITIVE: SearchOptions = SearchOptions {
+        case_sensitive: false,
+        whole_word: false,
+    };
+
+    const SENSITIVE: SearchOptions = SearchOptions {
+        case_sensitive: true,
+        whole_word: false,
+    };
+
+    const WHOLE_WORD: SearchOptions = SearchOptions {
+        case_sensitive: false,
+        whole_word: true,
+    };
+
+    #[test]
+    fn find_matches_case_sensitive() {
+        assert_eq!(vec![4..7], find_matches("Foo foo", "foo", SENSITIVE));
+        assert_eq!(
+            vec![0..3, 4..7],
+            find_matches("Foo foo", "foo", INSENSITIVE)
+        );
+    }
+
+    #[test]
+    fn find_matches_non_overlapping() {
+        assert_eq!(vec![0..2, 2..4], find_matches("aaaaa", "aa", SENSITIVE));
+    }
+
+    #[test]
+    fn find_matches_whole_word() {
+        let value = "cat cats cat_ concat (cat) Cat";
+
+        assert_eq!(
+            vec![0..3, 22..25, 27..30],
+            find_matches(value, "cat", WHOLE_WORD)
+        );
+    }
+
+    #[test]
+    fn find_matches_whole_word_after_rejection() {
+        assert_eq!(vec![4..6], find_matches("xab ab", "ab", WHOLE_WORD));
+    }
+
+    #[test]
+    fn find_matches_unicode_case() {
+        assert_eq!(vec![0..4], find_matches("ÄÖ", "äö", INSENSITIVE));
+        assert_eq!(vec![7..9], find_matches("日本 σ", "Σ", INSENSITIVE));
+    }
+
+    #[test]
+    fn find_matches_empty_query() {
+        assert!(find_matches("abc", "", INSENSITIVE).is_empty());
+    }
+
+    #[test]
+    fn find_all_positions() {
+        let text = Text::from("日本 ab\nAB 日本\n", (0, 0), true);
+
+        assert_eq!(
+            vec![Range::new((3, 0), (5, 0)), Range::new((0, 1), (2, 1))],
+            text.find_all("ab", INSENSITIVE)
+        );
+        assert_eq!(
+            vec![Range::new((3, 1), (0, 2))],
+            text.find_all("日本\n", INSENSITIVE)
+        );
+    }
+
+    #[test]
+    fn find_all_across_lines() {
+        let text = Text::from("one\ntwo\none\ntwo", (0, 0), true);
+
+        assert_eq!(
+            vec![Range::new((0, 0), (3, 1)), Range::new((0, 2), (3, 3))],
+            text.find_all("one\ntwo", SENSITIVE)
+        );
+    }
+
+    #[test]
+    fn find_all_skips_split_graphemes() {
+        let text = Text::from("e\u{301} e", (0, 0), true);
+
+        assert_eq!(
+            vec![Range::new((2, 0), (3, 0))],
+            text.find_all("e", SENSITIVE)
+        );
+    }
+
+    #[test]
+    fn find_next_wraps() {
+        let mut text = Text::from("ab\nab\nab", (1, 1), true);
+
+        assert_eq!(
+            Some(Range::new((0, 2), (2, 2))),
+            text.find_next("ab", SENSITIVE)
+        );
+        assert_eq!((2, 2), text.cursor());
+
+        assert_eq!(
+            Some(Range::new((0, 0), (2, 0))),
+            text.find_next("ab", SENSITIVE)
+        );
+        assert_eq!(Some(Range::new((0, 0), (2, 0))), text.selection());
+    }
+
+    #[test]
+    fn find_next_at_cursor() {
+        let mut text = Text::from("xx ab", (3, 0), true);
+
+        assert_eq!(
+            Some(Range::new((3, 0), (5, 0))),
+            text.find_next("ab", SENSITIVE)
+        );
+    }
+
+    #[test]
+    fn find_next_reversed_selection() {
+        let mut text = Text::from("ab ab ab", (0, 0), true);
+
+        text.set_selection((5, 0), (3, 0));
+
+        assert_eq!(
+            Some(Range::new((6, 0), (8, 0))),
+            text.find_next("ab", SENSITIVE)
+        );
+    }
+
+    #[test]
+    fn find_previous_wraps() {
+        let mut text = Text::from("ab ab ab", (4, 0), true);
+
+        assert_eq!(
+            Some(Range::new((3, 0), (5, 0))),
+            text.find_previous("ab", SENSITIVE)
+        );
+        assert_eq!(
+            Some(Range::new((0, 0), (2, 0))),
+            text.find_previous("ab", SENSITIVE)
+        );
+        assert_eq!(
+            Some(Range::new((6, 0), (8, 0))),
+            text.find_previous("ab", SENSITIVE)
+        );
+    }
+
+    #[test]
+    fn find_no_matches() {
+        let mut text = Text::from("abc", (1, 0), true);
+
+        assert_eq!(None, text.find_next("x", SENSITIVE));
+        assert_eq!(None, text.find_previous("x", SENSITIVE));
+        assert_eq!((1, 0), text.cursor());
+        assert_eq!(None, text.selection());
+    }
+
+    #[test]
+    fn find_regex_across_lines() {
+        let text = Text::from("ab\ncd\nef", (0, 0), true);
+        let pattern = Regex::new(r"b\nc|(?m)^e").unwrap();
+
+        assert_eq!(
+            vec![Range::new((1, 0), (1, 1)), Range::new((0, 2), (1, 2))],
+            text.find_regex(&pattern)
+        );
+    }
+
+    #[test]
+    fn find_regex_graphemes() {
+        let text = Text::from("日本e\u{301}x", (0, 0), true);
+
+        assert_eq!(
+            vec![Range::new((3, 0), (4, 0))],
+            text.find_regex(&Regex::new("x").unwrap())
+        );
+        assert!(text.find_regex(&Regex::new("e").unwrap()).is_empty());
+    }
+
+    #[test]
+    fn replace_regex_from_selection() {
+        let mut text = Text::from("a1 a2 a3", (3, 0), true);
+        let pattern = Regex::new(r"a(\d)").unwrap();
+
+        text.find_next("a", SENSITIVE);
+        assert_eq!(Some(Range::new((3, 0), (4, 0))), text.selection());
+
+        assert_eq!(
+            Some(Range::new((3, 0), (6, 0))),
+            text.replace_regex(&pattern, "<$1>")
+        );
+        assert_eq!("a1 <2> a3", text.value());
+        assert_eq!((6, 0), text.cursor());
+        assert_eq!(None, text.selection());
+    }
+
+    #[test]
+    fn replace_regex_wraps() {
+        let mut text = Text::from("a1 b a2", (7, 0), true);
+        let pattern = Regex::new(r"a(\d)").unwrap();
+
+        text.replace_regex(&pattern, "$1");
+        assert_eq!("1 b a2", text.value());
+        assert_eq!((1, 0), text.cursor());
+    }
+
+    #[test]
+    fn replace_regex_inserts_lines() {
+        let mut text = Text::from("a, b", (0, 0), true);
+
+        assert_eq!(
+            Some(Range::new((1, 0), (0, 1))),
+            text.replace_regex(&Regex::new(", ").unwrap(), "\n")
+        );
+        assert_eq!(vec!["a", "b"], text.lines().collect::<Vec<_>>());
+        assert_eq!((0, 1), text.cursor());
+    }
+
+    #[test]
+    fn replace_regex_named_groups() {
+        let mut text = Text::from("2024-05", (0, 0), true);
+        let pattern = Regex::new(r"(?<year>\d+)-(?<month>\d+)").unwrap();
+
+        text.replace_regex(&pattern, "${month}/$year");
+        assert_eq!("05/2024", text.value());
+    }
+
+    #[test]
+    fn replace_regex_no_matches() {
+        let mut text = Text::from("abc", (1, 0), true);
+
+        assert_eq!(None, text.replace_regex(&Regex::new("x").unwrap(), "y"));
+        assert_eq!(0, text.replace_all_regex(&Regex::new("x").unwrap(), "y"));
+        assert!(!text.undo());
+    }
+
+    #[test]
+    fn replace_all_regex_removes_lines() {
+        let mut text = Text::from("a\n\nb\n\n\nc", (1, 5), true);
+
+        assert_eq!(2, text.replace_all_regex(&Regex::new("\n+").unwrap(), " "));
+        assert_eq!("a b c", text.value());
+        assert_eq!(1, text.line_count());
+        assert_eq!((5, 0), text.cursor());
+    }
+
+    #[test]
+    fn replace_all_regex_adjusts_cursor() {
+        let mut text = Text::from("x x\nx yz", (2, 1), true);
+
+        text.replace_all_regex(&Regex::new("x").unwrap(), "a\nb");
+        assert_eq!("a\nb a\nb\na\nb yz", text.value());
+        assert_eq!((2, 4), text.cursor());
+        assert_eq!("b yz", text.line(4));
+    }
+
+    #[test]
+    fn replace_all_regex_undo() {
+        let mut text = Text::from("a1 a2", (0, 0), true);
+
+        text.replace_all_regex(&Regex::new(r"a(\d)").unwrap(), "$1$1");
+        assert_eq!("11 22", text.value());
+
+        assert!(text.undo());
+        assert_eq!("a1 a2", text.value());
+    }
+
+    #[test]
+    fn replace_all_regex_single_line() {
+        let mut text = Text::from("a,b", (0, 0), false);
+
+        text.replace_all_regex(&Regex::new(",").unwrap(), "\n");
+        assert_eq!("ab", text.value());
+        assert_eq!(1, text.line_count());
+    }
+
+    #[test]
+    fn incremental_search_jumps_live() {
+        let mut text = Text::from("cat\ncar cart", (1, 0), true);
+
+        text.handle_input(Key::Search);
+        assert_eq!(Some(""), text.search_query());
+
+        text.handle_input(Key::Char('c'));
+        assert_eq!(Some(Range::new((0, 1), (1, 1))), text.selection());
+
+        text.handle_input(Key::Char('a'));
+        text.handle_input(Key::Char('r'));
+        assert_eq!(Some(Range::new((0, 1), (3, 1))), text.selection());
+
+        text.handle_input(Key::Char('t'));
+        assert_eq!(Some(Range::new((4, 1), (8, 1))), text.selection());
+
+        text.handle_input(Key::Backspace);
+        assert_eq!(Some("car"), text.search_query());
+        assert_eq!(Some(Range::new((4, 1), (7, 1))), text.selection());
+        assert_eq!("cat\ncar cart", text.value());
+    }
+
+    #[test]
+    fn incremental_search_next_and_previous() {
+        let mut text = Text::from("ab ab ab", (0, 0), true);
+
+        text.handle_input(Key::Search);
+        text.handle_input(Key::Paste("ab".to_string()));
+        assert_eq!(Some(Range::new((0, 0), (2, 0))), text.selection());
+
+        text.handle_input(Key::Search);
+        assert_eq!(Some(Range::new((3, 0), (5, 0))), text.selection());
+
+        text.handle_input(Key::Down);
+        assert_eq!(Some(Range::new((6, 0), (8, 0))), text.selection());
+
+        text.handle_input(Key::Down);
+        assert_eq!(Some(Range::new((0, 0), (2, 0))), text.selection());
+
+        text.handle_input(Key::Up);
+        assert_eq!(Some(Range::new((6, 0), (8, 0))), text.selection());
+        assert_eq!(Some("ab"), text.search_query());
+    }
+
+    #[test]
+    fn incremental_search_no_match() {
+        let mut text = Text::from("abc abd", (0, 0), true);
+
+        text.handle_input(Key::Search);
+        text.handle_input(Key::Char('a'));
+        text.handle_input(Key::Char('b'));
+        text.handle_input(Key::Char('d'));
+        assert_eq!(Some(Range::new((4, 0), (7, 0))), text.selection());
+
+        text.handle_input(Key::Char('x'));
+        assert_eq!(Some(Range::new((4, 0), (7, 0))), text.selection());
+    }
+
+    #[test]
+    fn incremental_search_enter_accepts() {
+        let mut text = Text::from("foo bar", (0, 0), true);
+
+        text.handle_input(Key::Search);
+        text.handle_input(Key::Char('b'));
+        text.handle_input(Key::Enter);
+
+        assert_eq!(None, text.search_query());
+        assert_eq!(None, text.selection());
+        assert_eq!((5, 0), text.cursor());
+
+        text.handle_input(Key::Char('X'));
+        assert_eq!("foo bXar", text.value());
+    }
+
+    #[test]
+    fn incremental_search_escape_restores() {
+        let mut text = Text::from("one two", (1, 0), true);
+        text.set_selection((0, 0), (1, 0));
+
+        text.handle_input(Key::Search);
+        text.handle_input(Key::Char('t'));
+        assert_eq!(Some(Range::new((4, 0), (5, 0))), text.selection());
+
+        text.handle_input(Key::Escape);
+        assert_eq!(None, text.search_query());
+        assert_eq!((1, 0), text.cursor());
+        assert_eq!(Some(Range::new((0, 0), (1, 0))), text.selection());
+    }
+
+    #[test]
+    fn incremental_search_empty_query_restores() {
+        let mut text = Text::from("one two", (1, 0), true);
+
+        text.handle_input(Key::Search);
+        text.handle_input(Key::Char('t'));
+        text.handle_input(Key::Backspace);
+
+        assert_eq!((1, 0), text.cursor());
+        assert_eq!(None, text.selection());
+    }
+
+    #[test]
+    fn incremental_search_other_input_accepts() {
+        let mut text = Text::from("one two", (0, 0), true);
+
+        text.handle_input(Key::Search);
+        text.handle_input(Key::Char('t'));
+        text.handle_input(Key::Char('w'));
+        text.handle_input(Key::Right);
+
+        assert_eq!(None, text.search_query());
+        assert_eq!((6, 0), text.cursor());
+
+        text.handle_input(Key::Char('!'));
+        assert_eq!("one tw!o", text.value());
+    }
+
+    #[test]
+    fn incremental_search_options() {
+        let mut text = Text::from("Ab ab", (0, 0), true);
+
+        text.handle_input(Key::Search);
+        text.handle_input(Key::Char('a'));
+        assert_eq!(Some(Range::new((0, 0), (1, 0))), text.selection());
+
+        text.set_search_options(SENSITIVE);
+        assert_eq!(Some(Range::new((3, 0), (4, 0))), text.selection());
+        assert_eq!(SENSITIVE, text.search_options());
+    }
+
+    #[test]
+    fn escape_clears_selection() {
+        let mut text = Text::from("abc", (0, 0), true);
+        text.set_selection((0, 0), (2, 0));
+
+        text.handle_input(Key::Escape);
+        assert_eq!(None, text.selection());
+        assert_eq!((2, 0), text.cursor());
+    }
+}
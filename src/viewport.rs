@@ -0,0 +1,475 @@
+//! Scroll state for rendering an editor's value within a fixed-size area.
+
+use std::{borrow::Cow, ops::Range};
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::{Text, TextBuffer};
+
+/// A fixed-size window onto an editor's value which scrolls to keep the cursor visible.
+///
+/// The viewport doesn't observe the editor; call [Viewport::scroll_to_cursor] after handling
+/// input and before rendering.
+///
+/// # Examples
+/// ```
+/// use tty_text::{Text, Key, Viewport};
+///
+/// let mut text = Text::from("a\nb\nc\nd\ne\nf", (0, 0), true);
+/// let mut viewport = Viewport::new(80, 3);
+///
+/// text.set_cursor((0, 4));
+/// viewport.scroll_to_cursor(&text);
+///
+/// assert_eq!(2..5, viewport.visible_lines(&text));
+/// assert_eq!(Some((0, 2)), viewport.cursor_position(&text));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Viewport {
+    /// The viewport's width in display columns.
+    width: usize,
+
+    /// The viewport's height in lines.
+    height: usize,
+
+    /// The number of lines to keep visible above and below the cursor when scrolling.
+    scroll_margin: usize,
+
+    /// The index of the first visible line.
+    line_offset: usize,
+
+    /// The first visible display column.
+    column_offset: usize,
+}
+
+impl Viewport {
+    /// Create a new viewport of the specified size, scrolled to the top of the value.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            scroll_margin: 0,
+            line_offset: 0,
+            column_offset: 0,
+        }
+    }
+
+    /// This viewport's width in display columns.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// This viewport's height in lines.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Update this viewport's size, e.g. after the terminal is resized. The scroll offset is
+    /// adjusted on the next call to [Viewport::scroll_to_cursor].
+    pub fn set_size(&mut self, width: usize, height: usize) {
+        self.width = width;
+        self.height = height;
+    }
+
+    /// The number of lines kept visible above and below the cursor when scrolling.
+    pub fn scroll_margin(&self) -> usize {
+        self.scroll_margin
+    }
+
+    /// Update the number of lines kept visible above and below the cursor when scrolling. The
+    /// margin is reduced as needed for the cursor to fit within the viewport's height.
+    pub fn set_scroll_margin(&mut self, scroll_margin: usize) {
+        self.scroll_margin = scroll_margin;
+    }
+
+    /// The index of the first visible line.
+    pub fn line_offset(&self) -> usize {
+        self.line_offset
+    }
+
+    /// The first visible display column.
+    pub fn column_offset(&self) -> usize {
+        self.column_offset
+    }
+
+    /// Scroll this viewport the minimum amount needed to keep the editor's cursor visible and
+    /// outside the scroll margins, without scrolling past the end of the value. Horizontally, the
+    /// viewport scrolls to keep the cursor within its width, e.g. for a fixed-width single-line
+    /// field.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_text::{Text, Viewport};
+    ///
+    /// let mut text = Text::from("a\nb\nc\nd\ne\nf", (0, 0), true);
+    /// let mut viewport = Viewport::new(80, 4);
+    /// viewport.set_scroll_margin(1);
+    ///
+    /// text.set_cursor((0, 3));
+    /// viewport.scroll_to_cursor(&text);
+    /// assert_eq!(1, viewport.line_offset());
+    ///
+    /// text.set_cursor((0, 1));
+    /// viewport.scroll_to_cursor(&text);
+    /// assert_eq!(0, viewport.line_offset());
+    /// ```
+    pub fn scroll_to_cursor<B: TextBuffer>(&mut self, text: &Text<B>) {
+        if self.height == 0 {
+            return;
+        }
+
+        let cursor_line = text.cursor().line;
+        let margin = std::cmp::min(self.scroll_margin, (self.height - 1) / 2);
+
+        if cursor_line < self.line_offset + margin {
+            self.line_offset = cursor_line.saturating_sub(margin);
+        } else if cursor_line + margin >= self.line_offset + self.height {
+            self.line_offset = cursor_line + margin + 1 - self.height;
+        }
+
+        let max_offset = text.line_count().saturating_sub(self.height);
+        self.line_offset = std::cmp::min(self.line_offset, max_offset);
+
+        self.scroll_to_cursor_column(text);
+    }
+
+    /// Scroll this viewport horizontally the minimum amount needed to keep the editor's cursor
+    /// visible, aligning the offset to the cursor line's grapheme boundaries.
+    fn scroll_to_cursor_column<B: TextBuffer>(&mut self, text: &Text<B>) {
+        if self.width == 0 {
+            return;
+        }
+
+        let (cursor_column, cursor_line) = text.display_cursor();
+        let line = text.line(cursor_line);
+
+        if cursor_column < self.column_offset {
+            self.column_offset = cursor_column;
+        } else if cursor_column >= self.column_offset + self.width {
+            // Skip past any wide character which would only be partially visible
+            let min_offset = cursor_column + 1 - self.width;
+            self.column_offset = get_grapheme_columns(&line)
+                .find(|&column| column >= min_offset)
+                .unwrap_or(min_offset);
+        }
+
+        // Avoid leaving blank space after the line's end when the line is shortened
+        let line_width = text.line_display_width(cursor_line);
+        let max_offset = (line_width + 1).saturating_sub(self.width);
+        if self.column_offset > max_offset {
+            self.column_offset = get_grapheme_columns(&line)
+                .find(|&column| column >= max_offset)
+                .unwrap_or(max_offset);
+        }
+    }
+
+    /// The range of the editor's line indices currently visible in this viewport.
+    pub fn visible_lines<B: TextBuffer>(&self, text: &Text<B>) -> Range<usize> {
+        let line_count = text.line_count();
+
+        let start = std::cmp::min(self.line_offset, line_count);
+        let end = std::cmp::min(self.line_offset + self.height, line_count);
+
+        start..end
+    }
+
+    /// The portion of the specified line visible in this viewport, starting from the first
+    /// grapheme at or after the column offset. Wide characters which would only be partially
+    /// visible at either edge are omitted.
+    ///
+    /// # Panics
+    /// If the line index is out of bounds.
+    pub fn visible_slice<'a, B: TextBuffer>(
+        &self,
+        text: &'a Text<B>,
+        line_index: usize,
+    ) -> Cow<'a, str> {
+        let line = text.line(line_index);
+        let (start, end) = self.get_visible_bytes(&line);
+
+        crate::get_line_slice(line, start..end)
+    }
+
+    /// The visible portion of the cursor's line and the cursor's display column within it, for
+    /// drawing a fixed-width field.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_text::{Text, Viewport};
+    ///
+    /// let text = Text::from("Hello, world!", (13, 0), false);
+    /// let mut viewport = Viewport::new(8, 1);
+    ///
+    /// viewport.scroll_to_cursor(&text);
+    ///
+    /// assert_eq!((" world!".into(), 7), viewport.cursor_slice(&text));
+    /// ```
+    pub fn cursor_slice<'a, B: TextBuffer>(&self, text: &'a Text<B>) -> (Cow<'a, str>, usize) {
+        let (cursor_column, cursor_line) = text.display_cursor();
+
+        let line = text.line(cursor_line);
+        let (start, end) = self.get_visible_bytes(&line);
+        let start_column = line[..start].width();
+
+        (
+            crate::get_line_slice(line, start..end),
+            cursor_column.saturating_sub(start_column),
+        )
+    }
+
+    /// The editor's cursor position relative to this viewport as (display columns, rows), or
+    /// `None` if the cursor isn't currently visible.
+    pub fn cursor_position<B: TextBuffer>(&self, text: &Text<B>) -> Option<(usize, usize)> {
+        let (column, line) = text.display_cursor();
+
+        let visible_columns = self.column_offset..self.column_offset + self.width;
+        if !self.visible_lines(text).contains(&line) || !visible_columns.contains(&column) {
+            return None;
+        }
+
+        Some((column - self.column_offset, line - self.line_offset))
+    }
+
+    /// Get the byte range of the specified line's graphemes which fit entirely within this
+    /// viewport's visible columns.
+    fn get_visible_bytes(&self, line: &str) -> (usize, usize) {
+        let mut start = line.len();
+        let mut end = line.len();
+        let mut column = 0;
+
+        for (index, grapheme) in line.grapheme_indices(true) {
+            let grapheme_end = column + grapheme.width();
+
+            if column >= self.column_offset && start == line.len() {
+                start = index;
+            }
+
+            if grapheme_end > self.column_offset + self.width {
+                end = index;
+                break;
+            }
+
+            column = grapheme_end;
+        }
+
+        (std::cmp::min(start, end), end)
+    }
+}
+
+/// Get the display column at which each of the specified line's graphemes start.
+fn get_grapheme_columns(line: &str) -> impl Iterator<Item = usize> + '_ {
+    line.graphemes(true).scan(0, |column, grapheme| {
+        let start = *column;
+        *column += grapheme.width();
+        Some(start)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_with_lines(count: usize, cursor_line: usize) -> Text {
+        let value = vec!["line"; count].join("\n");
+        Text::from(&value, (0, cursor_line), true)
+    }
+
+    #[test]
+    fn new() {
+        let viewport = Viewport::new(10, 5);
+
+        assert_eq!(10, viewport.width());
+        assert_eq!(5, viewport.height());
+        assert_eq!(0, viewport.scroll_margin());
+        assert_eq!(0, viewport.line_offset());
+        assert_eq!(0, viewport.column_offset());
+    }
+
+    #[test]
+    fn scroll_down() {
+        let mut viewport = Viewport::new(10, 3);
+
+        viewport.scroll_to_cursor(&text_with_lines(10, 2));
+        assert_eq!(0, viewport.line_offset());
+
+        viewport.scroll_to_cursor(&text_with_lines(10, 3));
+        assert_eq!(1, viewport.line_offset());
+
+        viewport.scroll_to_cursor(&text_with_lines(10, 9));
+        assert_eq!(7, viewport.line_offset());
+    }
+
+    #[test]
+    fn scroll_up() {
+        let mut viewport = Viewport::new(10, 3);
+
+        viewport.scroll_to_cursor(&text_with_lines(10, 9));
+        viewport.scroll_to_cursor(&text_with_lines(10, 8));
+        assert_eq!(7, viewport.line_offset());
+
+        viewport.scroll_to_cursor(&text_with_lines(10, 4));
+        assert_eq!(4, viewport.line_offset());
+    }
+
+    #[test]
+    fn scroll_margin() {
+        let mut viewport = Viewport::new(10, 5);
+        viewport.set_scroll_margin(1);
+
+        viewport.scroll_to_cursor(&text_with_lines(10, 3));
+        assert_eq!(0, viewport.line_offset());
+
+        viewport.scroll_to_cursor(&text_with_lines(10, 4));
+        assert_eq!(1, viewport.line_offset());
+
+        viewport.scroll_to_cursor(&text_with_lines(10, 9));
+        assert_eq!(5, viewport.line_offset());
+
+        viewport.scroll_to_cursor(&text_with_lines(10, 6));
+        assert_eq!(5, viewport.line_offset());
+
+        viewport.scroll_to_cursor(&text_with_lines(10, 5));
+        assert_eq!(4, viewport.line_offset());
+    }
+
+    #[test]
+    fn scroll_margin_exceeds_height() {
+        let mut viewport = Viewport::new(10, 3);
+        viewport.set_scroll_margin(5);
+
+        viewport.scroll_to_cursor(&text_with_lines(10, 4));
+        assert_eq!(3, viewport.line_offset());
+        assert_eq!(
+            Some((0, 1)),
+            viewport.cursor_position(&text_with_lines(10, 4))
+        );
+    }
+
+    #[test]
+    fn scroll_clamped_to_value() {
+        let mut viewport = Viewport::new(10, 3);
+        viewport.scroll_to_cursor(&text_with_lines(10, 9));
+        assert_eq!(7, viewport.line_offset());
+
+        let shorter = text_with_lines(4, 3);
+        viewport.scroll_to_cursor(&shorter);
+        assert_eq!(1, viewport.line_offset());
+        assert_eq!(1..4, viewport.visible_lines(&shorter));
+    }
+
+    #[test]
+    fn visible_lines_short_value() {
+        let viewport = Viewport::new(10, 5);
+        assert_eq!(0..2, viewport.visible_lines(&text_with_lines(2, 0)));
+    }
+
+    #[test]
+    fn cursor_position() {
+        let mut viewport = Viewport::new(10, 3);
+        let text = Text::from("a\nb\nc\nd\n日本", (2, 4), true);
+
+        assert_eq!(None, viewport.cursor_position(&text));
+
+        viewport.scroll_to_cursor(&text);
+        assert_eq!(Some((4, 2)), viewport.cursor_position(&text));
+    }
+
+    #[test]
+    fn zero_height() {
+        let mut viewport = Viewport::new(10, 0);
+        let text = text_with_lines(5, 3);
+
+        viewport.scroll_to_cursor(&text);
+
+        assert_eq!(0, viewport.line_offset());
+        assert_eq!(0..0, viewport.visible_lines(&text));
+        assert_eq!(None, viewport.cursor_position(&text));
+    }
+
+    #[test]
+    fn scroll_right() {
+        let mut viewport = Viewport::new(5, 1);
+
+        viewport.scroll_to_cursor(&Text::from("abcdefghij", (4, 0), false));
+        assert_eq!(0, viewport.column_offset());
+
+        viewport.scroll_to_cursor(&Text::from("abcdefghij", (5, 0), false));
+        assert_eq!(1, viewport.column_offset());
+
+        viewport.scroll_to_cursor(&Text::from("abcdefghij", (10, 0), false));
+        assert_eq!(6, viewport.column_offset());
+    }
+
+    #[test]
+    fn scroll_left() {
+        let mut viewport = Viewport::new(5, 1);
+
+        viewport.scroll_to_cursor(&Text::from("abcdefghij", (10, 0), false));
+        viewport.scroll_to_cursor(&Text::from("abcdefghij", (7, 0), false));
+        assert_eq!(6, viewport.column_offset());
+
+        viewport.scroll_to_cursor(&Text::from("abcdefghij", (3, 0), false));
+        assert_eq!(3, viewport.column_offset());
+    }
+
+    #[test]
+    fn scroll_shortened_line() {
+        let mut viewport = Viewport::new(5, 1);
+
+        viewport.scroll_to_cursor(&Text::from("abcdefghij", (10, 0), false));
+        viewport.scroll_to_cursor(&Text::from("abcdef", (6, 0), false));
+
+        assert_eq!(2, viewport.column_offset());
+    }
+
+    #[test]
+    fn scroll_wide_characters() {
+        let mut viewport = Viewport::new(4, 1);
+        let text = Text::from("日本語です", (3, 0), false);
+
+        viewport.scroll_to_cursor(&text);
+
+        assert_eq!(4, viewport.column_offset());
+        assert_eq!("語で", viewport.visible_slice(&text, 0));
+        assert_eq!(("語で".into(), 2), viewport.cursor_slice(&text));
+        assert_eq!(Some((2, 0)), viewport.cursor_position(&text));
+    }
+
+    #[test]
+    fn visible_slice() {
+        let mut viewport = Viewport::new(4, 2);
+        let text = Text::from("abcdefgh\nab\n日本語", (6, 0), true);
+
+        viewport.scroll_to_cursor(&text);
+
+        assert_eq!(3, viewport.column_offset());
+        assert_eq!("defg", viewport.visible_slice(&text, 0));
+        assert_eq!("", viewport.visible_slice(&text, 1));
+        assert_eq!("語", viewport.visible_slice(&text, 2));
+    }
+
+    #[test]
+    fn cursor_slice() {
+        let mut viewport = Viewport::new(5, 1);
+        let text = Text::from("abcdefghij", (2, 0), false);
+
+        viewport.scroll_to_cursor(&text);
+        assert_eq!(("abcde".into(), 2), viewport.cursor_slice(&text));
+
+        let text = Text::from("abcdefghij", (8, 0), false);
+        viewport.scroll_to_cursor(&text);
+        assert_eq!(("efghi".into(), 4), viewport.cursor_slice(&text));
+    }
+
+    #[test]
+    fn cursor_position_scrolled_horizontally() {
+        let mut viewport = Viewport::new(5, 1);
+        let text = Text::from("abcdefghij", (8, 0), false);
+
+        assert_eq!(None, viewport.cursor_position(&text));
+
+        viewport.scroll_to_cursor(&text);
+        assert_eq!(Some((4, 0)), viewport.cursor_position(&text));
+    }
+}
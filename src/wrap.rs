@@ -0,0 +1,565 @@
+//! Soft wrapping of an editor's lines into visual rows of a fixed width.
+
+use std::borrow::Cow;
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::{Position, Text, TextBuffer};
+
+/// Where soft-wrapped lines may be broken into rows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WrapMode {
+    /// Break between any two grapheme clusters.
+    Character,
+
+    /// Break after whitespace where possible, falling back to breaking between grapheme clusters
+    /// for words wider than the wrap width. Whitespace at a break is kept at the end of the row
+    /// and may extend past the wrap width.
+    Word,
+}
+
+/// Soft wrapping configuration for an editor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SoftWrap {
+    /// The maximum width of a visual row in display columns.
+    pub width: usize,
+
+    /// Where lines may be broken into rows.
+    pub mode: WrapMode,
+}
+
+impl SoftWrap {
+    /// Create a new soft wrapping configuration.
+    pub fn new(width: usize, mode: WrapMode) -> Self {
+        Self { width, mode }
+    }
+}
+
+impl<B: TextBuffer> Text<B> {
+    /// This editor's soft wrapping configuration, if soft wrapping is enabled.
+    pub fn soft_wrap(&self) -> Option<SoftWrap> {
+        self.soft_wrap
+    }
+
+    /// Enable or disable soft wrapping. While enabled, vertical cursor movement is by visual row.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_text::{Text, Key, SoftWrap, WrapMode};
+    ///
+    /// let mut text = Text::from("Hello, world!\nBye", (9, 0), true);
+    /// text.set_soft_wrap(Some(SoftWrap::new(8, WrapMode::Word)));
+    ///
+    /// assert_eq!(vec!["Hello, ", "world!"], text.visual_rows(0));
+    /// assert_eq!((2, 1), text.visual_position(text.cursor()));
+    ///
+    /// text.handle_input(Key::Up);
+    /// assert_eq!((2, 0), text.cursor());
+    ///
+    /// text.handle_input(Key::Down);
+    /// text.handle_input(Key::Down);
+    /// assert_eq!((2, 1), text.cursor());
+    /// ```
+    pub fn set_soft_wrap(&mut self, soft_wrap: Option<SoftWrap>) {
+        self.soft_wrap = soft_wrap;
+        self.update_preferred_column();
+    }
+
+    /// The specified line's content split into its visual rows. Without soft wrapping, this is
+    /// the whole line.
+    ///
+    /// # Panics
+    /// If the line index is out of bounds.
+    pub fn visual_rows(&self, line_index: usize) -> Vec<Cow<'_, str>> {
+        let line = self.buffer.line(line_index);
+
+        let mut row_bytes: Vec<usize> = self
+            .get_row_starts(line_index)
+            .into_iter()
+            .map(|column| crate::get_byte_index(&line, column))
+            .collect();
+        row_bytes.push(line.len());
+
+        row_bytes
+            .windows(2)
+            .map(|bounds| crate::get_line_slice(line.clone(), bounds[0]..bounds[1]))
+            .collect()
+    }
+
+    /// The total number of visual rows across all lines. This wraps every line.
+    pub fn visual_row_count(&self) -> usize {
+        (0..self.buffer.line_count())
+            .map(|line_index| self.get_row_starts(line_index).len())
+            .sum()
+    }
+
+    /// Map the specified logical position to a visual (display columns, rows) position, accounting
+    /// for soft wrapping and character widths. A position at a wrap point is placed at the start of
+    /// the following row. The position will be clamped to the editor's current value.
+    ///
+    /// Rows are counted from the start of the value, so this wraps every line up to the position's.
+    pub fn visual_position(&self, position: impl Into<Position>) -> (usize, usize) {
+        let position = self.clamp_position(position.into().into());
+
+        let preceding_rows: usize = (0..position.1)
+            .map(|line_index| self.get_row_starts(line_index).len())
+            .sum();
+
+        let (x, row) = self.get_row_position(position);
+        (x, preceding_rows + row)
+    }
+
+    /// Map the specified visual (display columns, rows) position to the nearest logical position
+    /// at or before it. The position will be clamped to the editor's current value.
+    ///
+    /// Rows are counted from the start of the value, so this wraps every line up to the resulting
+    /// position's.
+    pub fn logical_position(&self, visual_position: (usize, usize)) -> Position {
+        let (x, mut y) = visual_position;
+
+        let mut line_index = 0;
+        let mut row_starts = self.get_row_starts(line_index);
+        while y >= row_starts.len() && line_index + 1 < self.buffer.line_count() {
+            y -= row_starts.len();
+            line_index += 1;
+            row_starts = self.get_row_starts(line_index);
+        }
+
+        let row = std::cmp::min(y, row_starts.len() - 1);
+
+        Position {
+            line: line_index,
+            column: self.get_row_column(line_index, &row_starts, row, x),
+        }
+    }
+
+    /// Attempt to move the editor's cursor by the specified number of visual rows, stopping at the
+    /// first or last row, and restoring the preferred display column if the row is long enough.
+    /// Only the lines from the cursor's to the target row's are wrapped.
+    pub(crate) fn move_visual_rows(&mut self, rows: isize) {
+        let (x, cursor_row) = self.get_row_position(self.cursor);
+
+        let mut line_index = self.cursor.1;
+        let mut row_starts = self.get_row_starts(line_index);
+        let mut row = cursor_row;
+        let mut remaining = rows.unsigned_abs();
+
+        if rows < 0 {
+            while remaining > row && line_index > 0 {
+                remaining -= row + 1;
+                line_index -= 1;
+                row_starts = self.get_row_starts(line_index);
+                row = row_starts.len() - 1;
+            }
+
+            row = row.saturating_sub(remaining);
+        } else {
+            while remaining >= row_starts.len() - row && line_index + 1 < self.buffer.line_count() {
+                remaining -= row_starts.len() - row;
+                line_index += 1;
+                row_starts = self.get_row_starts(line_index);
+                row = 0;
+            }
+
+            row = std::cmp::min(row + remaining, row_starts.len() - 1);
+        }
+
+        if (row, line_index) == (cursor_row, self.cursor.1) {
+            return;
+        }
+
+        let desired_x = std::cmp::max(x, self.preferred_column);
+        let column = self.get_row_column(line_index, &row_starts, row, desired_x);
+        self.cursor = (column, line_index);
+    }
+
+    /// Map the specified (columns, lines) position to a (display columns, rows) position within
+    /// its line's visual rows. Only the position's line is wrapped.
+    pub(crate) fn get_row_position(&self, position: (usize, usize)) -> (usize, usize) {
+        let (column, line_index) = position;
+
+        let row_starts = self.get_row_starts(line_index);
+        let row = row_starts.partition_point(|&start| start <= column) - 1;
+
+        let x = self
+            .buffer
+            .line(line_index)
+            .graphemes(true)
+            .skip(row_starts[row])
+            .take(column - row_starts[row])
+            .map(|grapheme| grapheme.width())
+            .sum();
+
+        (x, row)
+    }
+
+    /// Get the column of the nearest position at or before the specified display column within
+    /// the specified visual row of a line with the specified row starts.
+    fn get_row_column(
+        &self,
+        line_index: usize,
+        row_starts: &[usize],
+        row: usize,
+        x: usize,
+    ) -> usize {
+        let row_start = row_starts[row];
+
+        // Positions at a row's end belong to the next row, except on the line's last row
+        let row_end = match row_starts.get(row + 1) {
+            Some(&next_row_start) => std::cmp::max(next_row_start - 1, row_start),
+            None => self.get_line_length(line_index),
+        };
+
+        let mut column = row_start;
+        let mut row_x = 0;
+        for grapheme in self.buffer.line(line_index).graphemes(true).skip(row_start) {
+            row_x += grapheme.width();
+            if column == row_end || row_x > x {
+                break;
+            }
+
+            column += 1;
+        }
+
+        column
+    }
+
+    /// Get the grapheme column at which each of the specified line's visual rows start. The
+    /// first row always starts at column zero.
+    pub(crate) fn get_row_starts(&self, line_index: usize) -> Vec<usize> {
+        match self.soft_wrap {
+            Some(soft_wrap) => get_row_starts(&self.buffer.line(line_index), soft_wrap),
+            None => vec![0],
+        }
+    }
+}
+
+/// Get the grapheme column at which each of the specified line's visual rows start when wrapped
+/// with the specified configuration.
+fn get_row_starts(line: &str, soft_wrap: SoftWrap) -> Vec<usize> {
+    let wrap_width = std::cmp::max(soft_wrap.width, 1);
+    let widths: Vec<(usize, bool)> = line
+        .graphemes(true)
+        .map(|grapheme| (grapheme.width(), grapheme.trim().is_empty()))
+        .collect();
+
+    let mut row_starts = vec![0];
+    let mut row_width = 0;
+    let mut break_opportunity = None;
+
+    for (index, &(width, is_whitespace)) in widths.iter().enumerate() {
+        let row_start = *row_starts.last().unwrap_or(&0);
+
+        // Whitespace hangs at the end of a row and marks the following grapheme as a break point
+        if soft_wrap.mode == WrapMode::Word && is_whitespace {
+            row_width += width;
+            break_opportunity = Some(index + 1);
+            continue;
+        }
+
+        if row_width + width <= wrap_width || index == row_start {
+            row_width += width;
+            continue;
+        }
+
+        let new_row_start = match break_opportunity {
+            Some(opportunity) if opportunity > row_start => opportunity,
+            _ => index,
+        };
+
+        row_starts.push(new_row_start);
+        row_width = widths[new_row_start..=index]
+            .iter()
+            .map(|&(width, _)| width)
+            .sum();
+        break_opportunity = None;
+    }
+
+    row_starts
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::{Key, LineBuffer};
+
+    /// A buffer which counts how many times its lines' contents are read.
+    struct CountingBuffer {
+        buffer: LineBuffer,
+        reads: Cell<usize>,
+    }
+
+    impl TextBuffer for CountingBuffer {
+        fn line_count(&self) -> usize {
+            self.buffer.line_count()
+        }
+
+        fn line(&self, line_index: usize) -> Cow<'_, str> {
+            self.reads.set(self.reads.get() + 1);
+            self.buffer.line(line_index)
+        }
+
+        fn line_len(&self, line_index: usize) -> usize {
+            self.buffer.line_len(line_index)
+        }
+
+        fn insert(&mut self, position: (usize, usize), text: &str) {
+            self.buffer.insert(position, text);
+        }
+
+        fn remove(&mut self, start: (usize, usize), end: (usize, usize)) -> String {
+            self.buffer.remove(start, end)
+        }
+    }
+
+    fn wrapped(value: &str, cursor: (usize, usize), width: usize, mode: WrapMode) -> Text {
+        let mut text = Text::from(value, cursor, true);
+        text.set_soft_wrap(Some(SoftWrap::new(width, mode)));
+        text
+    }
+
+    #[test]
+    fn row_starts_character() {
+        let soft_wrap = SoftWrap::new(4, WrapMode::Character);
+
+        assert_eq!(vec![0], get_row_starts("", soft_wrap));
+        assert_eq!(vec![0], get_row_starts("abcd", soft_wrap));
+        assert_eq!(vec![0, 4, 8], get_row_starts("abcd efghij", soft_wrap));
+    }
+
+    #[test]
+    fn row_starts_word() {
+        let soft_wrap = SoftWrap::new(8, WrapMode::Word);
+
+        assert_eq!(vec![0], get_row_starts("abc def", soft_wrap));
+        assert_eq!(vec![0, 8], get_row_starts("abc def ghi", soft_wrap));
+        assert_eq!(vec![0, 9], get_row_starts("abc def  ghi jkl", soft_wrap));
+    }
+
+    #[test]
+    fn row_starts_word_too_long() {
+        let soft_wrap = SoftWrap::new(4, WrapMode::Word);
+
+        assert_eq!(vec![0, 3, 7], get_row_starts("ab abcdefg", soft_wrap));
+    }
+
+    #[test]
+    fn row_starts_wide_characters() {
+        let soft_wrap = SoftWrap::new(5, WrapMode::Character);
+
+        assert_eq!(vec![0, 2, 4], get_row_starts("日本語です", soft_wrap));
+    }
+
+    #[test]
+    fn row_starts_zero_width() {
+        let soft_wrap = SoftWrap::new(0, WrapMode::Character);
+
+        assert_eq!(vec![0, 1, 2], get_row_starts("abc", soft_wrap));
+    }
+
+    #[test]
+    fn visual_rows() {
+        let text = wrapped("abc def ghi\n\nxy", (0, 0), 8, WrapMode::Word);
+
+        assert_eq!(vec!["abc def ", "ghi"], text.visual_rows(0));
+        assert_eq!(vec![""], text.visual_rows(1));
+        assert_eq!(vec!["xy"], text.visual_rows(2));
+        assert_eq!(4, text.visual_row_count());
+    }
+
+    #[test]
+    fn visual_rows_unwrapped() {
+        let text = Text::from("abc def ghi", (0, 0), true);
+
+        assert_eq!(None, text.soft_wrap());
+        assert_eq!(vec!["abc def ghi"], text.visual_rows(0));
+        assert_eq!(1, text.visual_row_count());
+    }
+
+    #[test]
+    fn visual_position() {
+        let text = wrapped("abcdefghij\nklm", (0, 0), 4, WrapMode::Character);
+
+        assert_eq!((0, 0), text.visual_position((0, 0)));
+        assert_eq!((3, 0), text.visual_position((3, 0)));
+        assert_eq!((0, 1), text.visual_position((4, 0)));
+        assert_eq!((2, 2), text.visual_position((10, 0)));
+        assert_eq!((1, 3), text.visual_position((1, 1)));
+        assert_eq!((3, 3), text.visual_position((9, 9)));
+    }
+
+    #[test]
+    fn visual_position_wide_characters() {
+        let text = wrapped("a日本語", (0, 0), 4, WrapMode::Character);
+
+        assert_eq!((1, 0), text.visual_position((1, 0)));
+        assert_eq!((0, 1), text.visual_position((2, 0)));
+        assert_eq!((4, 1), text.visual_position((4, 0)));
+    }
+
+    #[test]
+    fn visual_position_unwrapped() {
+        let text = Text::from("日本\nabc", (0, 0), true);
+
+        assert_eq!((4, 0), text.visual_position((2, 0)));
+        assert_eq!((2, 1), text.visual_position((2, 1)));
+    }
+
+    #[test]
+    fn logical_position() {
+        let text = wrapped("abcdefghij\nklm", (0, 0), 4, WrapMode::Character);
+
+        assert_eq!((0, 0), text.logical_position((0, 0)));
+        assert_eq!((3, 0), text.logical_position((3, 0)));
+        assert_eq!((3, 0), text.logical_position((9, 0)));
+        assert_eq!((5, 0), text.logical_position((1, 1)));
+        assert_eq!((10, 0), text.logical_position((9, 2)));
+        assert_eq!((2, 1), text.logical_position((2, 3)));
+        assert_eq!((3, 1), text.logical_position((9, 9)));
+    }
+
+    #[test]
+    fn logical_position_wide_characters() {
+        let text = wrapped("a日本語", (0, 0), 4, WrapMode::Character);
+
+        assert_eq!((1, 0), text.logical_position((1, 0)));
+        assert_eq!((1, 0), text.logical_position((3, 0)));
+        assert_eq!((2, 0), text.logical_position((1, 1)));
+        assert_eq!((3, 0), text.logical_position((2, 1)));
+    }
+
+    #[test]
+    fn logical_position_round_trip() {
+        let text = wrapped("abc def ghi\njkl", (0, 0), 5, WrapMode::Word);
+
+        for position in [(0, 0), (3, 0), (4, 0), (8, 0), (11, 0), (2, 1)] {
+            let visual = text.visual_position(position);
+            assert_eq!(position, text.logical_position(visual));
+        }
+    }
+
+    #[test]
+    fn move_down_visual_row() {
+        let mut text = wrapped("abcdefghij\nklm", (1, 0), 4, WrapMode::Character);
+
+        text.handle_input(Key::Down);
+        assert_eq!((5, 0), text.cursor());
+
+        text.handle_input(Key::Down);
+        assert_eq!((9, 0), text.cursor());
+
+        text.handle_input(Key::Down);
+        assert_eq!((1, 1), text.cursor());
+
+        text.handle_input(Key::Down);
+        assert_eq!((1, 1), text.cursor());
+    }
+
+    #[test]
+    fn move_up_visual_row() {
+        let mut text = wrapped("abcdefghij\nklm", (2, 1), 4, WrapMode::Character);
+
+        text.handle_input(Key::Up);
+        assert_eq!((10, 0), text.cursor());
+
+        text.handle_input(Key::Up);
+        assert_eq!((6, 0), text.cursor());
+
+        text.handle_input(Key::Up);
+        assert_eq!((2, 0), text.cursor());
+
+        text.handle_input(Key::Up);
+        assert_eq!((2, 0), text.cursor());
+    }
+
+    #[test]
+    fn move_visual_row_preferred_column() {
+        let mut text = wrapped("abcdefgh ij abcdefgh", (7, 0), 9, WrapMode::Word);
+
+        text.handle_input(Key::Down);
+        assert_eq!((11, 0), text.cursor());
+
+        text.handle_input(Key::Down);
+        assert_eq!((19, 0), text.cursor());
+    }
+
+    #[test]
+    fn move_visual_row_single_line() {
+        let mut text = Text::from("abcdefghij", (1, 0), false);
+        text.set_soft_wrap(Some(SoftWrap::new(4, WrapMode::Character)));
+
+        text.handle_input(Key::Down);
+
+        assert_eq!((1, 0), text.cursor());
+    }
+
+    #[test]
+    fn move_page_display_column() {
+        let mut text = wrapped("abcdef\n日本語日本語", (6, 0), 20, WrapMode::Character);
+        text.set_page_height(1);
+
+        text.handle_input(Key::PageDown);
+        assert_eq!((3, 1), text.cursor());
+
+        text.handle_input(Key::PageUp);
+        assert_eq!((6, 0), text.cursor());
+    }
+
+    #[test]
+    fn move_page_visual_rows() {
+        let mut text = wrapped("abcdefghij\nklmn", (1, 0), 4, WrapMode::Character);
+        text.set_page_height(2);
+
+        text.handle_input(Key::PageDown);
+        assert_eq!((9, 0), text.cursor());
+
+        text.handle_input(Key::PageDown);
+        assert_eq!((1, 1), text.cursor());
+
+        text.handle_input(Key::PageUp);
+        assert_eq!((5, 0), text.cursor());
+
+        text.handle_input(Key::PageUp);
+        assert_eq!((1, 0), text.cursor());
+
+        text.handle_input(Key::PageUp);
+        assert_eq!((1, 0), text.cursor());
+    }
+
+    #[test]
+    fn large_document_wraps_near_cursor() {
+        let buffer = CountingBuffer {
+            buffer: LineBuffer::from(vec!["abc def ghi jkl"; 10_000].join("\n").as_str()),
+            reads: Cell::new(0),
+        };
+
+        let mut text = Text::with_buffer(buffer, (0, 5_000), true);
+        text.set_soft_wrap(Some(SoftWrap::new(8, WrapMode::Word)));
+        text.set_page_height(10);
+        text.buffer.reads.set(0);
+
+        let keys = [
+            Key::Char('x'),
+            Key::Backspace,
+            Key::Up,
+            Key::Down,
+            Key::PageUp,
+            Key::PageDown,
+            Key::Left,
+            Key::Right,
+        ];
+        for key in keys {
+            text.handle_input(key);
+        }
+
+        assert_eq!((0, 5_000), text.cursor());
+        assert!(
+            text.buffer.reads.get() < 100,
+            "{} lines read",
+            text.buffer.reads.get()
+        );
+    }
+}